@@ -10,6 +10,7 @@ import init, {
   validateSolution,
   validateGrid,
   isSolvable,
+  hasUniqueSolution,
   getHint,
   formatGrid,
   createEmptyGrid,
@@ -55,7 +56,8 @@ if (hintResult) {
 
 // 4. Check if puzzle is solvable
 console.log("4. Checking if puzzle is solvable...");
-console.log(`Is solvable: ${isSolvable(puzzle)}\n`);
+console.log(`Is solvable: ${isSolvable(puzzle)}`);
+console.log(`Has unique solution: ${hasUniqueSolution(puzzle)}\n`);
 
 // 5. Grid utilities
 console.log("5. Grid utilities demo...");
@@ -85,6 +87,7 @@ console.log(`Parse string: ${fromStr ? "✓" : "✗"}`);
 console.log("\n8. String API demo...");
 const puzzleStr = generate("hard");
 console.log(`Generated (hard): ${puzzleStr}`);
+console.log(`Seeded twice, same puzzle: ${generate("hard", 9, 123) === generate("hard", 9, 123)}`);
 
 const solutionStr = solve(puzzleStr);
 console.log(`Solved: ${solutionStr}`);
@@ -22,12 +22,16 @@ pub struct SudokuResult {
     pub solution: Grid,
 }
 
-/// Generates a new puzzle with the specified difficulty.
+/// Generates a new puzzle with the specified difficulty. The same seed, a
+/// `bigint` covering the full 64-bit range, always gives the same puzzle.
 /// @returns `{ puzzle: Grid, solution: Grid }`
 #[wasm_bindgen(js_name = "generateSudoku")]
-pub fn generate_sudoku(difficulty: Option<Difficulty>) -> Result<JsValue, String> {
+pub fn generate_sudoku(
+    difficulty: Option<Difficulty>,
+    seed: Option<u64>,
+) -> Result<JsValue, String> {
     let diff: SodoDifficulty = difficulty.unwrap_or(Difficulty::Medium).into();
-    let mut solver = Solver::new();
+    let mut solver = seeded(seed);
     let puzzle = solver.generate(9, diff)?;
     let solution = solver.solve(puzzle.clone())?;
 
@@ -65,13 +69,12 @@ pub fn validate_solution(puzzle: JsValue, solution: JsValue) -> Result<bool, Str
     }
 
     // Validate all rows, columns, boxes
-    for i in 0..9 {
+    for (i, row) in s.iter().enumerate() {
         let mut row_seen = [false; 10];
         let mut col_seen = [false; 10];
-        for j in 0..9 {
-            let row_val = s[i][j];
+        for (j, &row_val) in row.iter().enumerate() {
             let col_val = s[j][i];
-            if row_val < 1 || row_val > 9 || row_seen[row_val as usize] {
+            if !(1..=9).contains(&row_val) || row_seen[row_val as usize] {
                 return Ok(false);
             }
             if col_seen[col_val as usize] {
@@ -116,6 +119,13 @@ pub fn is_solvable_grid(grid: JsValue) -> Result<bool, String> {
     Ok(solver.solve(sudoku).is_ok())
 }
 
+/// Checks if a grid puzzle has exactly one solution.
+#[wasm_bindgen(js_name = "hasUniqueSolution")]
+pub fn has_unique_solution_grid(grid: JsValue) -> Result<bool, String> {
+    let g = parse_grid(grid)?;
+    Ok(from_grid(&g)?.has_unique_solution())
+}
+
 /// Gets a hint for the next logical move.
 /// @returns `{ row: number, col: number, value: number }` or `null`
 #[wasm_bindgen(js_name = "getHint")]
@@ -179,12 +189,17 @@ pub fn grid_to_string(grid: JsValue) -> Result<String, String> {
     Ok(from_grid(&g)?.to_string_compact())
 }
 
-/// Generates puzzle as compact string.
+/// Generates puzzle as compact string. The same seed, a `bigint` covering the
+/// full 64-bit range, always gives the same puzzle.
 #[wasm_bindgen]
-pub fn generate(difficulty: Option<String>, size: Option<usize>) -> Result<String, String> {
+pub fn generate(
+    difficulty: Option<String>,
+    size: Option<usize>,
+    seed: Option<u64>,
+) -> Result<String, String> {
     let size = size.unwrap_or(9);
     let diff: SodoDifficulty = parse_difficulty(difficulty.as_deref())?.into();
-    let mut solver = Solver::new();
+    let mut solver = seeded(seed);
     solver.generate(size, diff).map(|s| s.to_string_compact())
 }
 
@@ -234,6 +249,13 @@ impl From<Difficulty> for SodoDifficulty {
     }
 }
 
+fn seeded(seed: Option<u64>) -> Solver {
+    match seed {
+        Some(seed) => Solver::new().seed(seed),
+        None => Solver::new(),
+    }
+}
+
 fn parse_difficulty(s: Option<&str>) -> Result<Difficulty, String> {
     match s.unwrap_or("medium") {
         "easy" => Ok(Difficulty::Easy),
@@ -267,7 +289,7 @@ fn check_grid_format(grid: &Grid) -> Result<(), String> {
 
 fn to_grid(sudoku: &Sudoku) -> Grid {
     sudoku
-        .grid
+        .grid()
         .iter()
         .map(|row| row.iter().map(|c| c.value().unwrap_or(0)).collect())
         .collect()
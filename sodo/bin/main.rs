@@ -1,6 +1,6 @@
-use clap::{Parser, Subcommand, ValueEnum};
-use sodo::{Difficulty, Solver, Sudoku};
-use std::{fs, path::PathBuf, process};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use sodo::{Difficulty, Solver, Sudoku, strategy};
+use std::{fs, path::PathBuf, process, time::Duration};
 
 #[derive(Parser)]
 #[command(name = "sodo", version, about = "Sudoku solver and generator")]
@@ -22,6 +22,8 @@ enum Command {
         /// Grid size
         #[arg(short, long, default_value = "9")]
         size: usize,
+        #[command(flatten)]
+        strategies: Strategies,
     },
     /// Generate a new puzzle
     #[command(visible_alias = "g")]
@@ -32,6 +34,12 @@ enum Command {
         /// Difficulty level
         #[arg(short, long, default_value = "medium")]
         difficulty: Level,
+        /// Seed for reproducible output
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Give up after this many seconds
+        #[arg(short, long)]
+        timeout: Option<u64>,
     },
     /// Validate a puzzle
     #[command(visible_alias = "v")]
@@ -41,7 +49,7 @@ enum Command {
         /// Grid size
         #[arg(short, long, default_value = "9")]
         size: usize,
-        /// Also check if puzzle is solvable
+        /// Also check if puzzle is solvable, and uniquely so
         #[arg(short, long)]
         check: bool,
     },
@@ -53,7 +61,90 @@ enum Command {
         /// Grid size
         #[arg(short, long, default_value = "9")]
         size: usize,
+        /// Assume the puzzle has a unique solution
+        #[arg(short, long)]
+        unique: bool,
+        #[command(flatten)]
+        strategies: Strategies,
+    },
+    /// Rate puzzles on the Sudoku Explainer scale
+    #[command(visible_alias = "r")]
+    Rate {
+        /// Puzzle string
+        puzzle: Option<String>,
+        /// Read puzzles from file, one per line
+        #[arg(short, long, conflicts_with = "puzzle")]
+        file: Option<PathBuf>,
+        /// Grid size
+        #[arg(short, long, default_value = "9")]
+        size: usize,
+        /// Assume the puzzle has a unique solution
+        #[arg(short, long)]
+        unique: bool,
+        #[command(flatten)]
+        strategies: Strategies,
     },
+    /// Explain each logical step of the solution
+    #[command(visible_alias = "e")]
+    Explain {
+        /// Puzzle string
+        puzzle: String,
+        /// Grid size
+        #[arg(short, long, default_value = "9")]
+        size: usize,
+        /// Assume the puzzle has a unique solution
+        #[arg(short, long)]
+        unique: bool,
+        #[command(flatten)]
+        strategies: Strategies,
+    },
+}
+
+#[derive(Args)]
+struct Strategies {
+    /// Only use these strategies (comma-separated ids, e.g. naked-single,x-wing).
+    /// Naming one that assumes a unique solution, such as unique-rectangle,
+    /// turns that assumption on
+    #[arg(long, value_delimiter = ',')]
+    strategies: Vec<String>,
+    /// Never use these strategies (comma-separated ids)
+    #[arg(long, value_delimiter = ',')]
+    exclude: Vec<String>,
+}
+
+impl Strategies {
+    /// Builds a solver from the selected strategies, assuming a unique
+    /// solution if `unique` is set or a strategy needing it was named.
+    fn solver(&self, unique: bool) -> Solver {
+        let unknown = self
+            .strategies
+            .iter()
+            .chain(&self.exclude)
+            .find(|id| strategy::lookup(id).is_none());
+        if let Some(id) = unknown {
+            let ids: Vec<_> = strategy::registry().iter().map(|info| info.id).collect();
+            eprintln!("Unknown strategy: {id}\nAvailable: {}", ids.join(", "));
+            process::exit(1);
+        }
+
+        let ids: Vec<_> = strategy::registry()
+            .iter()
+            .map(|info| info.id)
+            .filter(|id| self.strategies.is_empty() || self.strategies.iter().any(|s| s == id))
+            .filter(|id| !self.exclude.iter().any(|s| s == id))
+            .collect();
+        let named_unique = self
+            .strategies
+            .iter()
+            .filter_map(|id| strategy::lookup(id))
+            .any(|info| info.build().requires_uniqueness());
+        Solver::from_ids(&ids)
+            .unwrap_or_else(|e| {
+                eprintln!("{e}");
+                process::exit(1)
+            })
+            .use_uniqueness(unique || named_unique)
+    }
 }
 
 #[derive(Clone, ValueEnum)]
@@ -79,34 +170,50 @@ fn main() {
     let cli = Cli::parse();
 
     match cli.command {
-        Command::Solve { puzzle, file, size } => solve(puzzle, file, size),
-        Command::Generate { size, difficulty } => generate(size, difficulty.into()),
+        Command::Solve {
+            puzzle,
+            file,
+            size,
+            strategies,
+        } => solve(puzzle, file, size, strategies.solver(false)),
+        Command::Generate {
+            size,
+            difficulty,
+            seed,
+            timeout,
+        } => generate(size, difficulty.into(), seed, timeout),
         Command::Validate {
             puzzle,
             size,
             check,
         } => validate(&puzzle, size, check),
-        Command::Hint { puzzle, size } => hint(&puzzle, size),
+        Command::Hint {
+            puzzle,
+            size,
+            unique,
+            strategies,
+        } => hint(&puzzle, size, strategies.solver(unique)),
+        Command::Rate {
+            puzzle,
+            file,
+            size,
+            unique,
+            strategies,
+        } => rate(puzzle, file, size, strategies.solver(unique)),
+        Command::Explain {
+            puzzle,
+            size,
+            unique,
+            strategies,
+        } => explain(&puzzle, size, strategies.solver(unique)),
     }
 }
 
-fn solve(puzzle: Option<String>, file: Option<PathBuf>, size: usize) {
-    let input = match (puzzle, file) {
-        (Some(p), _) => p,
-        (_, Some(f)) => fs::read_to_string(&f).unwrap_or_else(|e| {
-            eprintln!("Error reading {}: {e}", f.display());
-            process::exit(1);
-        }),
-        _ => {
-            eprintln!("Provide puzzle string or --file");
-            process::exit(1);
-        }
-    };
-
+fn solve(puzzle: Option<String>, file: Option<PathBuf>, size: usize, mut solver: Solver) {
+    let input = read_input(puzzle, file);
     let sudoku = parse(input.trim(), size);
     println!("Puzzle:\n{sudoku}");
 
-    let mut solver = Solver::new();
     match solver.solve_with_stats(sudoku) {
         Ok((solution, stats)) => {
             println!("Solution:\n{solution}");
@@ -122,8 +229,14 @@ fn solve(puzzle: Option<String>, file: Option<PathBuf>, size: usize) {
     }
 }
 
-fn generate(size: usize, difficulty: Difficulty) {
+fn generate(size: usize, difficulty: Difficulty, seed: Option<u64>, timeout: Option<u64>) {
     let mut solver = Solver::new();
+    if let Some(seed) = seed {
+        solver = solver.seed(seed);
+    }
+    if let Some(secs) = timeout {
+        solver = solver.time_limit(Duration::from_secs(secs));
+    }
     match solver.generate(size, difficulty) {
         Ok(puzzle) => {
             println!("{puzzle}");
@@ -152,8 +265,9 @@ fn validate(puzzle: &str, size: usize, check_solvable: bool) {
 
     if check_solvable {
         let mut solver = Solver::new();
-        match solver.solve(sudoku) {
-            Ok(_) => println!("Valid and solvable"),
+        match solver.solve(sudoku.clone()) {
+            Ok(_) if sudoku.has_unique_solution() => println!("Valid and uniquely solvable"),
+            Ok(_) => println!("Valid and solvable, but not uniquely"),
             Err(_) => {
                 println!("Valid but unsolvable!");
                 process::exit(1);
@@ -164,9 +278,8 @@ fn validate(puzzle: &str, size: usize, check_solvable: bool) {
     }
 }
 
-fn hint(puzzle: &str, size: usize) {
+fn hint(puzzle: &str, size: usize, solver: Solver) {
     let sudoku = parse(puzzle, size);
-    let solver = Solver::new();
 
     match solver.hint(&sudoku) {
         Some((r, c, v)) => println!("Place {v} at row {}, col {}", r + 1, c + 1),
@@ -174,6 +287,65 @@ fn hint(puzzle: &str, size: usize) {
     }
 }
 
+fn rate(puzzle: Option<String>, file: Option<PathBuf>, size: usize, solver: Solver) {
+    let input = read_input(puzzle, file);
+
+    for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let sudoku = parse(line, size);
+        match solver.rate(&sudoku) {
+            Some(rating) => {
+                let level = format!("{:?}", rating.difficulty()).to_lowercase();
+                println!("{rating} {:>5} {level:<6} {line}", rating.score)
+            }
+            None => println!("unrated {line}"),
+        }
+    }
+}
+
+fn explain(puzzle: &str, size: usize, solver: Solver) {
+    let sudoku = parse(puzzle, size);
+    println!("{sudoku}");
+
+    if !sudoku.is_valid() {
+        println!("Invalid!");
+        process::exit(1);
+    }
+
+    let steps = solver.explain(&sudoku);
+
+    for (i, step) in steps.iter().enumerate() {
+        println!("{:>3}. {step}", i + 1);
+    }
+
+    let mut result = sudoku;
+    for step in &steps {
+        step.apply(&mut result);
+    }
+
+    if result.is_solved() {
+        println!("\nSolved in {} steps", steps.len());
+    } else {
+        println!(
+            "\nStuck after {} steps; no further logical deduction found:\n{result}",
+            steps.len()
+        );
+    }
+}
+
+fn read_input(puzzle: Option<String>, file: Option<PathBuf>) -> String {
+    match (puzzle, file) {
+        (Some(p), _) => p,
+        (_, Some(f)) => fs::read_to_string(&f).unwrap_or_else(|e| {
+            eprintln!("Error reading {}: {e}", f.display());
+            process::exit(1);
+        }),
+        _ => {
+            eprintln!("Provide puzzle string or --file");
+            process::exit(1);
+        }
+    }
+}
+
 fn parse(s: &str, size: usize) -> Sudoku {
     Sudoku::from_string(s, size).unwrap_or_else(|e| {
         eprintln!("Invalid puzzle: {e}");
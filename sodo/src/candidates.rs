@@ -0,0 +1,239 @@
+use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A set of cell values stored as a bitmask, where bit `v - 1` holds value `v`.
+///
+/// A single `u64` covers every grid size up to 64x64, so the same type serves
+/// 4x4 through 64x64 puzzles without any allocation.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct Candidates(u64);
+
+impl Candidates {
+    /// The empty set.
+    pub const EMPTY: Self = Self(0);
+
+    /// Returns the set of all values `1..=size`.
+    #[inline]
+    pub fn all(size: usize) -> Self {
+        debug_assert!(size <= 64);
+        if size >= 64 {
+            Self(u64::MAX)
+        } else {
+            Self((1 << size) - 1)
+        }
+    }
+
+    /// Returns a set containing only `val`.
+    #[inline]
+    pub fn single(val: u8) -> Self {
+        debug_assert!((1..=64).contains(&val));
+        Self(1 << (val - 1))
+    }
+
+    /// Creates a set from its raw bitmask.
+    #[inline]
+    pub fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw bitmask.
+    #[inline]
+    pub fn bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the number of values in the set.
+    #[inline]
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if `val` is in the set.
+    #[inline]
+    pub fn contains(self, val: u8) -> bool {
+        (1..=64).contains(&val) && self.0 & (1 << (val - 1)) != 0
+    }
+
+    /// Adds `val` to the set.
+    #[inline]
+    pub fn insert(&mut self, val: u8) {
+        self.0 |= Self::single(val).0;
+    }
+
+    /// Removes `val` from the set. Returns true if it was present.
+    #[inline]
+    pub fn remove(&mut self, val: u8) -> bool {
+        let present = self.contains(val);
+        self.0 &= !Self::single(val).0;
+        present
+    }
+
+    /// Returns the smallest value in the set.
+    #[inline]
+    pub fn first(self) -> Option<u8> {
+        (self.0 != 0).then(|| self.0.trailing_zeros() as u8 + 1)
+    }
+
+    /// Returns the only value in the set, if it has exactly one.
+    #[inline]
+    pub fn single_value(self) -> Option<u8> {
+        (self.len() == 1).then(|| self.0.trailing_zeros() as u8 + 1)
+    }
+
+    /// Iterates over the values in ascending order.
+    #[inline]
+    pub fn iter(self) -> Iter {
+        Iter(self.0)
+    }
+}
+
+/// Iterator over the values of a [`Candidates`] set.
+#[derive(Debug, Clone)]
+pub struct Iter(u64);
+
+impl Iterator for Iter {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        if self.0 == 0 {
+            return None;
+        }
+        let v = self.0.trailing_zeros() as u8 + 1;
+        self.0 &= self.0 - 1;
+        Some(v)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.0.count_ones() as usize;
+        (n, Some(n))
+    }
+}
+
+impl ExactSizeIterator for Iter {}
+
+impl IntoIterator for Candidates {
+    type Item = u8;
+    type IntoIter = Iter;
+
+    #[inline]
+    fn into_iter(self) -> Iter {
+        self.iter()
+    }
+}
+
+impl FromIterator<u8> for Candidates {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        let mut set = Self::EMPTY;
+        for v in iter {
+            set.insert(v);
+        }
+        set
+    }
+}
+
+impl BitOr for Candidates {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Candidates {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for Candidates {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl BitAndAssign for Candidates {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl Sub for Candidates {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 & !rhs.0)
+    }
+}
+
+impl fmt::Debug for Candidates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_covers_every_size() {
+        assert_eq!(Candidates::all(4).bits(), 0b1111);
+        assert_eq!(Candidates::all(9).len(), 9);
+        assert_eq!(Candidates::all(64).bits(), u64::MAX);
+        assert!(Candidates::all(0).is_empty());
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut set = Candidates::EMPTY;
+        set.insert(3);
+        set.insert(64);
+        assert!(set.contains(3) && set.contains(64));
+        assert!(!set.contains(4));
+        assert!(set.remove(3));
+        assert!(!set.remove(3));
+        assert_eq!(set.single_value(), Some(64));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: Candidates = [1, 2, 3].into_iter().collect();
+        let b: Candidates = [3, 4].into_iter().collect();
+        assert_eq!((a | b).iter().collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert_eq!(a & b, Candidates::single(3));
+        assert_eq!((a - b).iter().collect::<Vec<_>>(), [1, 2]);
+
+        let mut c = a;
+        c &= b;
+        c |= Candidates::single(9);
+        assert_eq!(c.iter().collect::<Vec<_>>(), [3, 9]);
+    }
+
+    #[test]
+    fn iterates_in_order() {
+        let set = Candidates::from_bits(0b1010_0100);
+        assert_eq!(set.first(), Some(3));
+        assert_eq!(set.single_value(), None);
+        assert_eq!(set.iter().len(), 3);
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), [3, 6, 8]);
+        assert_eq!(format!("{set:?}"), "{3, 6, 8}");
+    }
+}
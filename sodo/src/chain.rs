@@ -0,0 +1,476 @@
+use crate::sodo::Sudoku;
+use crate::step::{Pos, Unit};
+use crate::strategy::sees;
+use std::collections::VecDeque;
+use std::fmt;
+
+/// A chain node: one digit in a cell, or in a group of cells within one unit
+/// that acts as a single node (true if the digit is in any of them).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Node {
+    pub cells: Vec<(usize, usize)>,
+    pub digit: u8,
+}
+
+impl Node {
+    /// Creates a node for a single cell.
+    pub fn cell(row: usize, col: usize, digit: u8) -> Self {
+        Self {
+            cells: vec![(row, col)],
+            digit,
+        }
+    }
+}
+
+/// The kind of inference between two consecutive chain nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Link {
+    /// If the first node is false, the second is true (`=`).
+    Strong,
+    /// If the first node is true, the second is false (`-`).
+    Weak,
+}
+
+/// An alternating inference chain. `links[i]` joins `nodes[i]` and `nodes[i + 1]`.
+///
+/// Displays in Eureka notation, e.g. `(4=7)r2c3-(7=1)r2c8`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chain {
+    pub nodes: Vec<Node>,
+    pub links: Vec<Link>,
+}
+
+impl Chain {
+    /// Returns the number of links.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+}
+
+impl fmt::Display for Chain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut i = 0;
+
+        while i < self.nodes.len() {
+            if i > 0 {
+                write!(f, "{}", symbol(self.links[i - 1]))?;
+            }
+
+            // Consecutive nodes in the same cells collapse into one term: (4=7)r2c3.
+            write!(f, "({}", self.nodes[i].digit)?;
+            while i + 1 < self.nodes.len() && self.nodes[i + 1].cells == self.nodes[i].cells {
+                write!(f, "{}{}", symbol(self.links[i]), self.nodes[i + 1].digit)?;
+                i += 1;
+            }
+            write!(f, "){}", Cells(&self.nodes[i].cells))?;
+            i += 1;
+        }
+
+        Ok(())
+    }
+}
+
+fn symbol(link: Link) -> char {
+    match link {
+        Link::Strong => '=',
+        Link::Weak => '-',
+    }
+}
+
+/// Formats a set of cells compactly, e.g. `r1c78` or `r12c3`.
+struct Cells<'a>(&'a [(usize, usize)]);
+
+impl fmt::Display for Cells<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let &[(r, c)] = self.0 {
+            return write!(f, "{}", Pos(r, c));
+        }
+
+        let mut rows: Vec<_> = self.0.iter().map(|&(r, _)| r + 1).collect();
+        let mut cols: Vec<_> = self.0.iter().map(|&(_, c)| c + 1).collect();
+        rows.sort_unstable();
+        rows.dedup();
+        cols.sort_unstable();
+        cols.dedup();
+
+        let join = |v: &[usize]| {
+            let sep = if v.iter().any(|&x| x > 9) { "," } else { "" };
+            v.iter().map(usize::to_string).collect::<Vec<_>>().join(sep)
+        };
+        write!(f, "r{}c{}", join(&rows), join(&cols))
+    }
+}
+
+/// Which links a chain search may use.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Rules {
+    /// Only build nodes in cells with exactly two candidates.
+    pub bivalue_only: bool,
+    /// Strong links between the only two places for a digit in a unit.
+    pub conjugate: bool,
+    /// Strong links between the two digits of a bivalue cell.
+    pub bivalue: bool,
+    /// Weak links between different digits of the same cell.
+    pub cell_weak: bool,
+    /// Grouped nodes: a digit's candidates where a box meets a row or column.
+    pub groups: bool,
+}
+
+/// A chain together with what it proves.
+pub(crate) struct Deduction {
+    pub chain: Chain,
+    pub placements: Vec<(usize, usize, u8)>,
+    pub eliminations: Vec<(usize, usize, u8)>,
+}
+
+impl Deduction {
+    /// Returns true if any node of the chain is grouped.
+    pub fn is_grouped(&self) -> bool {
+        self.chain.nodes.iter().any(|n| n.cells.len() > 1)
+    }
+}
+
+/// The two kinds of nice loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LoopKind {
+    /// Links alternate all the way round: every weak link acts as a strong one.
+    Continuous,
+    /// Two links of the same kind meet at the start node, deciding it.
+    Discontinuous,
+}
+
+/// The strong and weak links between the candidates of a grid.
+pub(crate) struct Graph {
+    nodes: Vec<Node>,
+    strong: Vec<Vec<usize>>,
+    weak: Vec<Vec<usize>>,
+    box_size: usize,
+}
+
+impl Graph {
+    /// Builds the link graph of a puzzle under the given rules.
+    pub fn new(sudoku: &Sudoku, rules: Rules) -> Self {
+        let (n, bs) = (sudoku.size, sudoku.box_size);
+        let mut nodes = Vec::new();
+
+        for r in 0..n {
+            for c in 0..n {
+                let cands = sudoku.candidates(r, c);
+                if rules.bivalue_only && cands.len() != 2 {
+                    continue;
+                }
+                nodes.extend(cands.iter().map(|d| Node::cell(r, c, d)));
+            }
+        }
+
+        if rules.groups {
+            for b in 0..n {
+                let lines = (0..bs).map(|i| Unit::Row(b / bs * bs + i));
+                let lines = lines.chain((0..bs).map(|i| Unit::Col(b % bs * bs + i)));
+                for line in lines {
+                    for digit in 1..=n as u8 {
+                        let cells: Vec<_> = Unit::Box(b)
+                            .cells(bs)
+                            .filter(|&(r, c)| {
+                                line.contains(r, c, bs) && sudoku.candidates(r, c).contains(digit)
+                            })
+                            .collect();
+                        if cells.len() >= 2 {
+                            nodes.push(Node { cells, digit });
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut strong = vec![Vec::new(); nodes.len()];
+        let mut weak = vec![Vec::new(); nodes.len()];
+
+        for (i, a) in nodes.iter().enumerate() {
+            for (j, b) in nodes.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+
+                if a.cells.len() == 1 && a.cells == b.cells {
+                    let (r, c) = a.cells[0];
+                    if rules.bivalue && sudoku.candidates(r, c).len() == 2 {
+                        strong[i].push(j);
+                    }
+                    if rules.cell_weak {
+                        weak[i].push(j);
+                    }
+                } else if a.digit == b.digit
+                    && a.cells
+                        .iter()
+                        .all(|&x| b.cells.iter().all(|&y| sees(x, y, bs)))
+                {
+                    weak[i].push(j);
+                    if rules.conjugate && conjugate(sudoku, a, b) {
+                        strong[i].push(j);
+                    }
+                }
+            }
+        }
+
+        Self {
+            nodes,
+            strong,
+            weak,
+            box_size: bs,
+        }
+    }
+
+    /// Finds the shortest chain of at most `max_len` links that eliminates
+    /// something: each chain starts and ends with a strong link, so one of its
+    /// ends is true and any candidate weakly linked to both can be removed.
+    pub fn shortest_elimination(&self, sudoku: &Sudoku, max_len: usize) -> Option<Deduction> {
+        let mut best: Option<Deduction> = None;
+
+        for start in 0..self.nodes.len() {
+            let limit = best.as_ref().map_or(max_len, |d| d.chain.len() - 1);
+            let targets = self.weak_candidates(sudoku, &self.nodes[start]);
+            if targets.is_empty() {
+                continue;
+            }
+
+            let found = self.search(start, false, limit, |path, on| {
+                let &end = path.last()?;
+                if !on || end == start {
+                    return None;
+                }
+                let end = &self.nodes[end];
+                let elims: Vec<_> = targets
+                    .iter()
+                    .copied()
+                    .filter(|&(r, c, d)| self.weakly_linked(end, (r, c), d))
+                    .collect();
+                (!elims.is_empty()).then_some(elims)
+            });
+
+            if let Some((path, eliminations)) = found {
+                best = Some(Deduction {
+                    chain: self.chain(&path, Link::Strong),
+                    placements: Vec::new(),
+                    eliminations,
+                });
+            }
+        }
+
+        best
+    }
+
+    /// Finds the shortest nice loop of at most `max_len` links that proves
+    /// something.
+    pub fn shortest_loop(&self, sudoku: &Sudoku, max_len: usize) -> Option<(LoopKind, Deduction)> {
+        let mut best: Option<(LoopKind, Deduction)> = None;
+
+        for start in 0..self.nodes.len() {
+            let limit = |best: &Option<(LoopKind, Deduction)>| {
+                best.as_ref().map_or(max_len, |(_, d)| d.chain.len() - 1)
+            };
+            let node = &self.nodes[start];
+
+            // Discontinuous, two strong links: assuming the start false makes it true.
+            if let &[(r, c)] = node.cells.as_slice() {
+                let found = self.search(start, false, limit(&best), |path, on| {
+                    (on && path.len() > 2 && path.last() == Some(&start)).then_some(())
+                });
+                if let Some((path, ())) = found {
+                    let deduction = Deduction {
+                        chain: self.chain(&path, Link::Strong),
+                        placements: vec![(r, c, node.digit)],
+                        eliminations: Vec::new(),
+                    };
+                    best = Some((LoopKind::Discontinuous, deduction));
+                }
+            }
+
+            // Discontinuous, two weak links: assuming the start true makes it false.
+            let found = self.search(start, true, limit(&best), |path, on| {
+                (!on && path.len() > 2 && path.last() == Some(&start)).then_some(())
+            });
+            if let Some((path, ())) = found {
+                let eliminations: Vec<_> = node
+                    .cells
+                    .iter()
+                    .map(|&(r, c)| (r, c, node.digit))
+                    .collect();
+                let deduction = Deduction {
+                    chain: self.chain(&path, Link::Weak),
+                    placements: Vec::new(),
+                    eliminations,
+                };
+                best = Some((LoopKind::Discontinuous, deduction));
+            }
+
+            // Continuous: the chain returns to the start through a weak link.
+            let found = self.search(start, false, limit(&best).saturating_sub(1), |path, on| {
+                let &end = path.last()?;
+                let simple = path.iter().enumerate().all(|(i, x)| !path[..i].contains(x));
+                if !on || path.len() < 4 || !simple || !self.weak[end].contains(&start) {
+                    return None;
+                }
+
+                let mut closed = path.to_vec();
+                closed.push(start);
+                let elims = self.loop_eliminations(sudoku, &closed);
+                (!elims.is_empty()).then_some(elims)
+            });
+            if let Some((mut path, eliminations)) = found {
+                path.push(start);
+                let deduction = Deduction {
+                    chain: self.chain(&path, Link::Strong),
+                    placements: Vec::new(),
+                    eliminations,
+                };
+                best = Some((LoopKind::Continuous, deduction));
+            }
+        }
+
+        best
+    }
+
+    /// Breadth-first search from `start` along alternating strong and weak
+    /// links. A node implied false continues along strong links, one implied
+    /// true along weak links. `check` is called with the path to every state
+    /// reached and whether its last node is true, and ends the search when it
+    /// returns `Some`.
+    fn search<T>(
+        &self,
+        start: usize,
+        start_on: bool,
+        max_len: usize,
+        mut check: impl FnMut(&[usize], bool) -> Option<T>,
+    ) -> Option<(Vec<usize>, T)> {
+        // State `2 * node + on`: `on` is 1 when the node is implied true.
+        let origin = 2 * start + usize::from(start_on);
+        let mut parent = vec![usize::MAX; self.nodes.len() * 2];
+        let mut depth = vec![0; self.nodes.len() * 2];
+        let mut queue = VecDeque::from([origin]);
+        parent[origin] = origin;
+
+        while let Some(state) = queue.pop_front() {
+            let (node, on) = (state / 2, state % 2 == 1);
+
+            if state != origin {
+                let mut path = vec![node];
+                let mut s = state;
+                while s != origin {
+                    s = parent[s];
+                    path.push(s / 2);
+                }
+                path.reverse();
+
+                if let Some(found) = check(&path, on) {
+                    return Some((path, found));
+                }
+            }
+
+            if depth[state] >= max_len {
+                continue;
+            }
+
+            let next = if on {
+                &self.weak[node]
+            } else {
+                &self.strong[node]
+            };
+            for &m in next {
+                let s = 2 * m + usize::from(!on);
+                if parent[s] == usize::MAX {
+                    parent[s] = state;
+                    depth[s] = depth[state] + 1;
+                    queue.push_back(s);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Builds a chain from a path of node indices, alternating from `first`.
+    fn chain(&self, path: &[usize], first: Link) -> Chain {
+        let other = match first {
+            Link::Strong => Link::Weak,
+            Link::Weak => Link::Strong,
+        };
+        Chain {
+            nodes: path.iter().map(|&i| self.nodes[i].clone()).collect(),
+            links: (0..path.len() - 1)
+                .map(|i| if i % 2 == 0 { first } else { other })
+                .collect(),
+        }
+    }
+
+    /// Eliminations of a continuous loop given as a closed path that starts
+    /// with a strong link: exactly one end of every weak link is true, so any
+    /// candidate weakly linked to both ends goes.
+    fn loop_eliminations(&self, sudoku: &Sudoku, closed: &[usize]) -> Vec<(usize, usize, u8)> {
+        let mut elims = Vec::new();
+
+        for i in (1..closed.len() - 1).step_by(2) {
+            let (x, y) = (&self.nodes[closed[i]], &self.nodes[closed[i + 1]]);
+            for (r, c, d) in self.weak_candidates(sudoku, x) {
+                if self.weakly_linked(y, (r, c), d) && !elims.contains(&(r, c, d)) {
+                    elims.push((r, c, d));
+                }
+            }
+        }
+
+        elims
+    }
+
+    /// Returns the candidates of the grid that are false whenever `node` is true.
+    fn weak_candidates(&self, sudoku: &Sudoku, node: &Node) -> Vec<(usize, usize, u8)> {
+        let n = sudoku.size;
+        let mut out = Vec::new();
+
+        for r in 0..n {
+            for c in 0..n {
+                for d in sudoku.candidates(r, c) {
+                    if self.weakly_linked(node, (r, c), d) {
+                        out.push((r, c, d));
+                    }
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Returns true if `digit` in `cell` is false whenever `node` is true.
+    fn weakly_linked(&self, node: &Node, cell: (usize, usize), digit: u8) -> bool {
+        if node.cells.contains(&cell) {
+            return node.digit != digit && node.cells.len() == 1;
+        }
+        node.digit == digit && node.cells.iter().all(|&x| sees(x, cell, self.box_size))
+    }
+}
+
+/// Returns true if `a` and `b` hold every candidate for their digit in some
+/// unit containing both.
+fn conjugate(sudoku: &Sudoku, a: &Node, b: &Node) -> bool {
+    let bs = sudoku.box_size;
+    let (r, c) = a.cells[0];
+    let cells = || a.cells.iter().chain(&b.cells);
+
+    [
+        Unit::Row(r),
+        Unit::Col(c),
+        Unit::Box(sudoku.box_index(r, c)),
+    ]
+    .into_iter()
+    .filter(|unit| cells().all(|&(r, c)| unit.contains(r, c, bs)))
+    .any(|unit| {
+        unit.cells(bs)
+            .filter(|&(r, c)| sudoku.candidates(r, c).contains(a.digit))
+            .all(|cell| a.cells.contains(&cell) || b.cells.contains(&cell))
+    })
+}
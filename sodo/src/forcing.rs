@@ -0,0 +1,249 @@
+use crate::candidates::Candidates;
+use crate::sodo::{Cell, Sudoku};
+use crate::step::Pos;
+use crate::strategy::{candidates_at, sees, units};
+use std::fmt;
+
+/// One assumption of a forcing chain and the placements that follow from it.
+///
+/// Displays as `r1c1=3 -> r1c5=7 -> r4c5=2`, ending in `contradiction` when
+/// the assumption is impossible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Branch {
+    /// Placements in the order they are forced, starting with the assumption.
+    pub placements: Vec<(usize, usize, u8)>,
+    /// True if the placements leave a cell or a unit with nowhere to go.
+    pub contradiction: bool,
+}
+
+impl fmt::Display for Branch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let steps: Vec<_> = self
+            .placements
+            .iter()
+            .map(|&(r, c, v)| format!("{}={v}", Pos(r, c)))
+            .collect();
+        write!(f, "{}", steps.join(" -> "))?;
+        if self.contradiction {
+            write!(f, " -> contradiction")?;
+        }
+        Ok(())
+    }
+}
+
+/// The singles forced by placing one value, with the reason for each.
+pub(crate) struct Propagation<'a> {
+    base: &'a Sudoku,
+    state: Sudoku,
+    /// Placements in the order they were made; the first is the assumption.
+    facts: Vec<(usize, usize, u8)>,
+    /// Indices of the earlier facts each fact depends on.
+    parents: Vec<Vec<usize>>,
+    /// Facts that together leave a cell or unit empty.
+    contradiction: Option<Vec<usize>>,
+}
+
+impl<'a> Propagation<'a> {
+    /// Places `val` at `cell` and follows the singles it forces for at most
+    /// `depth` rounds. With `digit` set, only hidden singles of that digit are
+    /// followed and only its units are checked.
+    pub fn assume(
+        base: &'a Sudoku,
+        (row, col): (usize, usize),
+        val: u8,
+        digit: Option<u8>,
+        depth: usize,
+    ) -> Self {
+        let mut state = base.clone();
+        state.put(row, col, Cell::Filled(val));
+
+        let mut prop = Self {
+            base,
+            state,
+            facts: vec![(row, col, val)],
+            parents: vec![Vec::new()],
+            contradiction: None,
+        };
+        prop.check(digit);
+
+        for _ in 0..depth {
+            if prop.contradiction.is_some() {
+                break;
+            }
+            let singles = prop.singles(digit);
+            if singles.is_empty() {
+                break;
+            }
+
+            for ((r, c, v), parents) in singles {
+                // An earlier single this round may have taken the cell or digit.
+                if prop.state.candidates(r, c).contains(v) {
+                    prop.state.put(r, c, Cell::Filled(v));
+                    prop.facts.push((r, c, v));
+                    prop.parents.push(parents);
+                }
+            }
+            prop.check(digit);
+        }
+
+        prop
+    }
+
+    /// Returns true if the assumption led to a contradiction.
+    #[inline]
+    pub fn is_contradiction(&self) -> bool {
+        self.contradiction.is_some()
+    }
+
+    /// Returns the placements made, starting with the assumption.
+    #[inline]
+    pub fn facts(&self) -> &[(usize, usize, u8)] {
+        &self.facts
+    }
+
+    /// Returns the branch proving the contradiction, if there is one.
+    pub fn contradiction(&self) -> Option<Branch> {
+        let roots = self.contradiction.as_ref()?;
+        Some(Branch {
+            placements: self.proof(roots),
+            contradiction: true,
+        })
+    }
+
+    /// Returns the branch proving that `val` goes at `cell`, if it does.
+    pub fn placement(&self, cell: (usize, usize), val: u8) -> Option<Branch> {
+        let i = self
+            .facts
+            .iter()
+            .position(|&f| f == (cell.0, cell.1, val))?;
+        Some(Branch {
+            placements: self.proof(&[i]),
+            contradiction: false,
+        })
+    }
+
+    /// Returns the branch proving that `val` can't go at `cell`, if it can't.
+    pub fn elimination(&self, cell: (usize, usize), val: u8) -> Option<Branch> {
+        let (r, c) = cell;
+        if !self.base.candidates(r, c).contains(val) || self.state.candidates(r, c).contains(val) {
+            return None;
+        }
+
+        let i = self.cause(cell, val)?;
+        Some(Branch {
+            placements: self.proof(&[i]),
+            contradiction: false,
+        })
+    }
+
+    /// Finds every single in the current state, with the facts behind it.
+    fn singles(&self, digit: Option<u8>) -> Vec<((usize, usize, u8), Vec<usize>)> {
+        let (n, bs) = (self.state.size, self.state.box_size);
+        let mut out = Vec::new();
+
+        if digit.is_none() {
+            for r in 0..n {
+                for c in 0..n {
+                    if let Some(v) = self.state.candidates(r, c).single_value() {
+                        let others = self.base.candidates(r, c) - Candidates::single(v);
+                        out.push(((r, c, v), self.causes(others.iter().map(|e| ((r, c), e)))));
+                    }
+                }
+            }
+        }
+
+        let digits = digit.map_or(Candidates::all(n), Candidates::single);
+        for unit in units(n) {
+            for v in digits {
+                let mut places = unit
+                    .cells(bs)
+                    .filter(|&(r, c)| self.state.candidates(r, c).contains(v));
+                let (Some((r, c)), None) = (places.next(), places.next()) else {
+                    continue;
+                };
+                if out.iter().any(|(f, _)| *f == (r, c, v)) {
+                    continue;
+                }
+
+                let others = unit
+                    .cells(bs)
+                    .filter(|&cell| cell != (r, c) && candidates_at(self.base, cell).contains(v));
+                out.push(((r, c, v), self.causes(others.map(|cell| (cell, v)))));
+            }
+        }
+
+        out
+    }
+
+    /// Records a contradiction if some cell or unit has nowhere left to go.
+    fn check(&mut self, digit: Option<u8>) {
+        let (n, bs) = (self.state.size, self.state.box_size);
+
+        if digit.is_none() {
+            for r in 0..n {
+                for c in 0..n {
+                    if self.state.grid[r][c].is_empty() && self.state.candidates(r, c).is_empty() {
+                        let gone = self.base.candidates(r, c).iter().map(|e| ((r, c), e));
+                        self.contradiction = Some(self.causes(gone));
+                        return;
+                    }
+                }
+            }
+        }
+
+        let digits = digit.map_or(Candidates::all(n), Candidates::single);
+        for unit in units(n) {
+            for v in digits {
+                let open = unit.cells(bs).any(|(r, c)| {
+                    self.state.grid[r][c].value() == Some(v)
+                        || self.state.candidates(r, c).contains(v)
+                });
+                if !open {
+                    let gone = unit
+                        .cells(bs)
+                        .filter(|&cell| candidates_at(self.base, cell).contains(v))
+                        .map(|cell| (cell, v));
+                    self.contradiction = Some(self.causes(gone));
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Returns the facts removing each of the given candidates.
+    fn causes(&self, gone: impl Iterator<Item = ((usize, usize), u8)>) -> Vec<usize> {
+        let mut out: Vec<_> = gone.filter_map(|(cell, v)| self.cause(cell, v)).collect();
+        out.sort_unstable();
+        out.dedup();
+        out
+    }
+
+    /// Returns the first fact removing `val` from `cell`: a placement in the
+    /// cell itself, or of `val` in a cell it sees.
+    fn cause(&self, cell: (usize, usize), val: u8) -> Option<usize> {
+        let bs = self.state.box_size;
+        self.facts.iter().position(|&(r, c, v)| {
+            ((r, c) == cell && v != val) || (v == val && sees((r, c), cell, bs))
+        })
+    }
+
+    /// Returns the facts needed to derive `roots`, in the order they were made.
+    fn proof(&self, roots: &[usize]) -> Vec<(usize, usize, u8)> {
+        let mut needed = vec![false; self.facts.len()];
+        needed[0] = true;
+
+        let mut stack = roots.to_vec();
+        while let Some(i) = stack.pop() {
+            if !needed[i] {
+                needed[i] = true;
+                stack.extend(&self.parents[i]);
+            }
+        }
+
+        self.facts
+            .iter()
+            .zip(needed)
+            .filter_map(|(&fact, needed)| needed.then_some(fact))
+            .collect()
+    }
+}
@@ -15,10 +15,20 @@
 //! assert!(solution.is_solved());
 //! ```
 
+mod candidates;
+mod chain;
+mod forcing;
+mod rating;
 mod sodo;
 mod solver;
-mod strategy;
+mod step;
+pub mod strategy;
 
+pub use candidates::Candidates;
+pub use chain::{Chain, Link, Node};
+pub use forcing::Branch;
+pub use rating::{Rating, Thresholds};
 pub use sodo::{Cell, Sudoku};
 pub use solver::{Difficulty, Solver, Stats};
+pub use step::{Detail, Step, Unit};
 pub use strategy::{Strategy, all as all_strategies};
@@ -0,0 +1,172 @@
+use crate::solver::Difficulty;
+use std::fmt;
+
+/// Difficulty of a puzzle, measured by solving it one easiest step at a time.
+///
+/// The `er`, `ep` and `ed` values are step difficulties on the Sudoku
+/// Explainer scale, from about 1.0 (a hidden single in a box) to 10.0; see
+/// [`Strategy::difficulty`](crate::Strategy::difficulty). The `score` sums
+/// HoDoKu-style per-step scores, and with `level` maps into a [`Difficulty`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Rating {
+    /// Difficulty of the hardest step in the solution (ER).
+    pub er: f32,
+    /// Difficulty of the first step (EP).
+    pub ep: f32,
+    /// Difficulty of the hardest step up to and including the first
+    /// placement (ED).
+    pub ed: f32,
+    /// Sum of the scores of every step.
+    pub score: u32,
+    /// Highest level among the strategies used.
+    pub level: Difficulty,
+    /// Side length of the grid rated, e.g. 9.
+    pub size: usize,
+}
+
+impl Rating {
+    /// Classifies the puzzle with the default [`Thresholds`] for its size.
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty_with(&Thresholds::for_size(self.size))
+    }
+
+    /// Classifies the puzzle as the level of its hardest strategy, raised to
+    /// the highest level whose score threshold it reaches.
+    pub fn difficulty_with(&self, thresholds: &Thresholds) -> Difficulty {
+        let by_score = if self.score >= thresholds.expert {
+            Difficulty::Expert
+        } else if self.score >= thresholds.hard {
+            Difficulty::Hard
+        } else if self.score >= thresholds.medium {
+            Difficulty::Medium
+        } else {
+            Difficulty::Easy
+        };
+        self.level.max(by_score)
+    }
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ER {:.1}/EP {:.1}/ED {:.1}", self.er, self.ep, self.ed)
+    }
+}
+
+/// Minimum scores for each [`Difficulty`] above easy, for one grid size.
+///
+/// The defaults keep a 9x9 puzzle needing only singles under 1000 points, so
+/// the level of the hardest strategy decides unless a puzzle needs many
+/// harder steps. Scores grow with the number of cells to fill, so other sizes
+/// use [`Thresholds::for_size`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Thresholds {
+    pub medium: u32,
+    pub hard: u32,
+    pub expert: u32,
+}
+
+impl Thresholds {
+    /// Returns the default thresholds scaled from 9x9 to a grid of the given
+    /// size by its number of cells.
+    pub fn for_size(size: usize) -> Self {
+        let cells = (size * size) as u64;
+        let scale = |score: u32| (u64::from(score) * cells / 81) as u32;
+        let base = Self::default();
+        Self {
+            medium: scale(base.medium),
+            hard: scale(base.hard),
+            expert: scale(base.expert),
+        }
+    }
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            medium: 1000,
+            hard: 1600,
+            expert: 2400,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rating(score: u32, level: Difficulty) -> Rating {
+        Rating {
+            score,
+            level,
+            size: 9,
+            ..Rating::default()
+        }
+    }
+
+    #[test]
+    fn easy_below_every_threshold() {
+        assert_eq!(rating(999, Difficulty::Easy).difficulty(), Difficulty::Easy);
+    }
+
+    #[test]
+    fn medium_by_score_or_level() {
+        assert_eq!(
+            rating(1000, Difficulty::Easy).difficulty(),
+            Difficulty::Medium
+        );
+        assert_eq!(
+            rating(0, Difficulty::Medium).difficulty(),
+            Difficulty::Medium
+        );
+    }
+
+    #[test]
+    fn hard_by_score_or_level() {
+        assert_eq!(
+            rating(1600, Difficulty::Medium).difficulty(),
+            Difficulty::Hard
+        );
+        assert_eq!(rating(700, Difficulty::Hard).difficulty(), Difficulty::Hard);
+    }
+
+    #[test]
+    fn expert_by_score_or_level() {
+        assert_eq!(
+            rating(2400, Difficulty::Easy).difficulty(),
+            Difficulty::Expert
+        );
+        assert_eq!(
+            rating(700, Difficulty::Expert).difficulty(),
+            Difficulty::Expert
+        );
+    }
+
+    #[test]
+    fn score_never_lowers_the_level() {
+        assert_eq!(rating(0, Difficulty::Hard).difficulty(), Difficulty::Hard);
+    }
+
+    #[test]
+    fn thresholds_scale_with_the_grid() {
+        assert_eq!(Thresholds::for_size(9), Thresholds::default());
+        assert_eq!(
+            Thresholds::for_size(16),
+            Thresholds {
+                medium: 3160,
+                hard: 5056,
+                expert: 7585,
+            }
+        );
+
+        // A 16x16 puzzle of singles scores far above the 9x9 thresholds.
+        let singles = Rating {
+            size: 16,
+            ..rating(2128, Difficulty::Easy)
+        };
+        assert_eq!(singles.difficulty(), Difficulty::Easy);
+        assert_eq!(
+            singles.difficulty_with(&Thresholds::default()),
+            Difficulty::Hard
+        );
+    }
+}
@@ -1,4 +1,5 @@
-use std::collections::HashSet;
+use crate::candidates::Candidates;
+use crate::solver::Solver;
 use std::fmt;
 
 #[cfg(feature = "serde")]
@@ -35,24 +36,115 @@ impl Cell {
 }
 
 /// A Sudoku puzzle grid.
+///
+/// Alongside the cells, the grid keeps a bitmask of the values present in
+/// every row, column and box, so candidate lookups never rescan the grid.
+/// It also keeps per-cell pencil marks: candidates that have been explicitly
+/// eliminated stay eliminated until [`Sudoku::reset_candidates`] is called.
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "Layout", try_from = "Layout")
+)]
 pub struct Sudoku {
-    pub grid: Vec<Vec<Cell>>,
+    pub(crate) grid: Vec<Vec<Cell>>,
     pub size: usize,
     pub box_size: usize,
+    rows: Vec<Candidates>,
+    cols: Vec<Candidates>,
+    boxes: Vec<Candidates>,
+    marks: Vec<Candidates>,
+}
+
+/// Serialized form of a [`Sudoku`]; the occupancy masks are rebuilt on load.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct Layout {
+    grid: Vec<Vec<Cell>>,
+    size: usize,
+    box_size: usize,
+    #[serde(default)]
+    marks: Vec<Candidates>,
+}
+
+#[cfg(feature = "serde")]
+impl From<Sudoku> for Layout {
+    fn from(s: Sudoku) -> Self {
+        Self {
+            grid: s.grid,
+            size: s.size,
+            box_size: s.box_size,
+            marks: s.marks,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<Layout> for Sudoku {
+    type Error = String;
+
+    fn try_from(layout: Layout) -> Result<Self, String> {
+        let size = layout.size;
+        let box_size = (size as f64).sqrt() as usize;
+        if size == 0 || size > 64 || box_size * box_size != size || box_size != layout.box_size {
+            return Err(format!(
+                "Invalid size {size} with box size {}",
+                layout.box_size
+            ));
+        }
+        if layout.grid.len() != size || layout.grid.iter().any(|row| row.len() != size) {
+            return Err(format!("Grid must be {size}x{size}"));
+        }
+
+        let mut sudoku = Self::new(size);
+        for (r, row) in layout.grid.into_iter().enumerate() {
+            for (c, cell) in row.into_iter().enumerate() {
+                if let Some(v) = cell.value()
+                    && (v == 0 || v as usize > size)
+                {
+                    return Err(format!(
+                        "Value {v} out of range at row {}, col {}",
+                        r + 1,
+                        c + 1
+                    ));
+                }
+                sudoku.put(r, c, cell);
+            }
+        }
+        if !layout.marks.is_empty() {
+            if layout.marks.len() != sudoku.marks.len() {
+                return Err(format!("Marks must cover all {} cells", size * size));
+            }
+            let all = Candidates::all(size);
+            if let Some(i) = layout.marks.iter().position(|&m| !(m - all).is_empty()) {
+                return Err(format!(
+                    "Marks out of range at row {}, col {}",
+                    i / size + 1,
+                    i % size + 1
+                ));
+            }
+            sudoku.marks = layout.marks;
+        }
+        Ok(sudoku)
+    }
 }
 
 impl Sudoku {
-    /// Creates an empty Sudoku of the given size.
+    /// Creates an empty Sudoku of the given size (at most 64).
     pub fn new(size: usize) -> Self {
         let box_size = (size as f64).sqrt() as usize;
         assert!(box_size * box_size == size, "Size must be a perfect square");
+        assert!(size <= 64, "Size must be at most 64");
 
         Self {
             grid: vec![vec![Cell::Empty; size]; size],
             size,
             box_size,
+            rows: vec![Candidates::EMPTY; size],
+            cols: vec![Candidates::EMPTY; size],
+            boxes: vec![Candidates::EMPTY; size],
+            marks: vec![Candidates::all(size); size * size],
         }
     }
 
@@ -68,13 +160,14 @@ impl Sudoku {
 
         for (i, &ch) in chars.iter().enumerate() {
             let (r, c) = (i / size, i % size);
-            sudoku.grid[r][c] = match ch {
+            let cell = match ch {
                 '0' | '.' | ' ' => Cell::Empty,
                 _ => Cell::Given(
                     parse_char(ch, size)
                         .ok_or_else(|| format!("Invalid char '{ch}' at ({r},{c})"))?,
                 ),
             };
+            sudoku.put(r, c, cell);
         }
 
         Ok(sudoku)
@@ -86,6 +179,12 @@ impl Sudoku {
         self.grid.get(row).and_then(|r| r.get(col).copied())
     }
 
+    /// Returns the rows of the grid.
+    #[inline]
+    pub fn grid(&self) -> &[Vec<Cell>] {
+        &self.grid
+    }
+
     /// Sets the cell value. Use 0 to clear.
     pub fn set(&mut self, row: usize, col: usize, val: u8) -> Result<(), String> {
         if row >= self.size || col >= self.size {
@@ -95,14 +194,38 @@ impl Sudoku {
             return Err(format!("Value {val} exceeds max {}", self.size));
         }
 
-        self.grid[row][col] = if val == 0 {
+        let cell = if val == 0 {
             Cell::Empty
         } else {
             Cell::Filled(val)
         };
+        self.put(row, col, cell);
         Ok(())
     }
 
+    /// Writes a cell and keeps the occupancy masks in sync.
+    pub(crate) fn put(&mut self, row: usize, col: usize, cell: Cell) {
+        let b = self.box_index(row, col);
+        let old = std::mem::replace(&mut self.grid[row][col], cell);
+
+        if old.value().is_some() {
+            // A unit may hold duplicates, so rebuild rather than clear the bit.
+            self.rows[row] = self.row_cells(row).filter_map(Cell::value).collect();
+            self.cols[col] = self.col_cells(col).filter_map(Cell::value).collect();
+            let bs = self.box_size;
+            self.boxes[b] = self
+                .box_cells(b / bs, b % bs)
+                .filter_map(Cell::value)
+                .collect();
+        }
+
+        if let Some(v) = cell.value() {
+            self.rows[row].insert(v);
+            self.cols[col].insert(v);
+            self.boxes[b].insert(v);
+        }
+    }
+
     /// Checks if the puzzle satisfies all Sudoku constraints.
     pub fn is_valid(&self) -> bool {
         self.valid_rows() && self.valid_cols() && self.valid_boxes()
@@ -125,8 +248,12 @@ impl Sudoku {
     }
 
     fn valid_unit(&self, cells: impl Iterator<Item = Cell>) -> bool {
-        let mut seen = HashSet::new();
-        cells.filter_map(|c| c.value()).all(|v| seen.insert(v))
+        let mut seen = Candidates::EMPTY;
+        cells.filter_map(|c| c.value()).all(|v| {
+            let fresh = !seen.contains(v);
+            seen.insert(v);
+            fresh
+        })
     }
 
     /// Checks if a value can be placed at (row, col).
@@ -135,29 +262,7 @@ impl Sudoku {
             return false;
         }
 
-        let target = Some(val);
-
-        // Row
-        if self.grid[row].iter().any(|c| c.value() == target) {
-            return false;
-        }
-
-        // Column
-        if (0..self.size).any(|r| self.grid[r][col].value() == target) {
-            return false;
-        }
-
-        // Box
-        let (br, bc) = self.box_origin(row, col);
-        for r in br..br + self.box_size {
-            for c in bc..bc + self.box_size {
-                if self.grid[r][c].value() == target {
-                    return false;
-                }
-            }
-        }
-
-        true
+        !self.used(row, col).contains(val)
     }
 
     /// Returns true if all cells are filled.
@@ -170,6 +275,12 @@ impl Sudoku {
         self.is_complete() && self.is_valid()
     }
 
+    /// Checks if the puzzle has exactly one solution. Eliminated candidates
+    /// are ignored.
+    pub fn has_unique_solution(&self) -> bool {
+        Solver::count(self.clone(), 2) == 1
+    }
+
     /// Counts empty cells.
     pub fn empty_count(&self) -> usize {
         self.grid.iter().flatten().filter(|c| c.is_empty()).count()
@@ -187,39 +298,45 @@ impl Sudoku {
         None
     }
 
-    /// Returns possible values for an empty cell.
-    pub fn candidates(&self, row: usize, col: usize) -> HashSet<u8> {
+    /// Returns possible values for an empty cell, excluding eliminated ones.
+    #[inline]
+    pub fn candidates(&self, row: usize, col: usize) -> Candidates {
         if !self.grid[row][col].is_empty() {
-            return HashSet::new();
+            return Candidates::EMPTY;
         }
+        self.marks[row * self.size + col] - self.used(row, col)
+    }
 
-        let mut cands: HashSet<u8> = (1..=self.size as u8).collect();
-
-        // Row
-        for c in &self.grid[row] {
-            if let Some(v) = c.value() {
-                cands.remove(&v);
-            }
+    /// Eliminates `val` from the pencil marks of (row, col).
+    ///
+    /// Returns true if `val` was a candidate of that cell.
+    pub fn eliminate(&mut self, row: usize, col: usize, val: u8) -> bool {
+        if row >= self.size || col >= self.size || val == 0 || val > self.size as u8 {
+            return false;
         }
 
-        // Column
-        for r in 0..self.size {
-            if let Some(v) = self.grid[r][col].value() {
-                cands.remove(&v);
-            }
-        }
+        let present = self.candidates(row, col).contains(val);
+        self.marks[row * self.size + col].remove(val);
+        present
+    }
 
-        // Box
-        let (br, bc) = self.box_origin(row, col);
-        for r in br..br + self.box_size {
-            for c in bc..bc + self.box_size {
-                if let Some(v) = self.grid[r][c].value() {
-                    cands.remove(&v);
-                }
-            }
-        }
+    /// Clears all eliminations, restoring every candidate allowed by the grid.
+    pub fn reset_candidates(&mut self) {
+        self.marks.fill(Candidates::all(self.size));
+    }
+
+    /// Counts candidates over all empty cells.
+    pub fn candidate_count(&self) -> usize {
+        (0..self.size)
+            .flat_map(|r| (0..self.size).map(move |c| (r, c)))
+            .map(|(r, c)| self.candidates(r, c).len())
+            .sum()
+    }
 
-        cands
+    /// Returns the values already present in the row, column and box of a cell.
+    #[inline]
+    fn used(&self, row: usize, col: usize) -> Candidates {
+        self.rows[row] | self.cols[col] | self.boxes[self.box_index(row, col)]
     }
 
     fn row_cells(&self, r: usize) -> impl Iterator<Item = Cell> + '_ {
@@ -236,10 +353,11 @@ impl Sudoku {
         (sr..sr + bs).flat_map(move |r| (sc..sc + bs).map(move |c| self.grid[r][c]))
     }
 
+    /// Returns the index of the box containing (row, col), counted row-major.
     #[inline]
-    fn box_origin(&self, r: usize, c: usize) -> (usize, usize) {
+    pub fn box_index(&self, r: usize, c: usize) -> usize {
         let bs = self.box_size;
-        (r / bs * bs, c / bs * bs)
+        r / bs * bs + c / bs
     }
 
     /// Returns a compact string representation (81 chars for 9x9).
@@ -291,3 +409,105 @@ fn parse_char(ch: char, size: usize) -> Option<u8> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+    /// Checks the occupancy masks against a fresh scan of the grid.
+    fn assert_masks_in_sync(s: &Sudoku) {
+        let bs = s.box_size;
+        for i in 0..s.size {
+            let row: Candidates = s.row_cells(i).filter_map(Cell::value).collect();
+            let col: Candidates = s.col_cells(i).filter_map(Cell::value).collect();
+            let boxed: Candidates = s
+                .box_cells(i / bs, i % bs)
+                .filter_map(Cell::value)
+                .collect();
+            assert_eq!(s.rows[i], row, "row {i}");
+            assert_eq!(s.cols[i], col, "col {i}");
+            assert_eq!(s.boxes[i], boxed, "box {i}");
+        }
+    }
+
+    #[test]
+    fn masks_follow_set() {
+        let mut s = Sudoku::from_string(PUZZLE, 9).unwrap();
+        assert_masks_in_sync(&s);
+
+        s.set(0, 2, 4).unwrap();
+        assert_masks_in_sync(&s);
+        assert!(!s.can_place(0, 3, 4));
+        assert!(!s.can_place(8, 2, 4));
+        assert!(!s.can_place(2, 0, 4));
+
+        s.set(0, 2, 0).unwrap();
+        assert_masks_in_sync(&s);
+        assert!(s.can_place(0, 2, 4));
+    }
+
+    #[test]
+    fn masks_survive_duplicates() {
+        // Clearing one of two 5s in row 1 must keep 5 in the row mask.
+        let mut s = Sudoku::from_string(PUZZLE, 9).unwrap();
+        s.put(0, 8, Cell::Filled(5));
+        assert!(!s.is_valid());
+        s.put(0, 8, Cell::Empty);
+        assert_masks_in_sync(&s);
+        assert!(s.is_valid());
+        assert!(!s.candidates(0, 8).contains(5));
+    }
+
+    #[test]
+    fn candidates_exclude_used_values() {
+        let s = Sudoku::from_string(PUZZLE, 9).unwrap();
+        assert_eq!(s.candidates(0, 2).iter().collect::<Vec<_>>(), [1, 2, 4]);
+        assert!(s.candidates(0, 0).is_empty());
+    }
+
+    #[test]
+    fn eliminations_persist() {
+        let mut s = Sudoku::from_string(PUZZLE, 9).unwrap();
+        assert!(s.eliminate(0, 2, 2));
+        assert!(!s.eliminate(0, 2, 2));
+        assert!(!s.eliminate(0, 2, 5), "5 is already used in the row");
+        assert_eq!(s.candidates(0, 2).iter().collect::<Vec<_>>(), [1, 4]);
+
+        // Placing and clearing a value elsewhere leaves the mark removed.
+        s.set(0, 3, 1).unwrap();
+        assert_eq!(s.candidates(0, 2).iter().collect::<Vec<_>>(), [4]);
+        s.set(0, 3, 0).unwrap();
+        assert_eq!(s.candidates(0, 2).iter().collect::<Vec<_>>(), [1, 4]);
+
+        s.reset_candidates();
+        assert_eq!(s.candidates(0, 2).iter().collect::<Vec<_>>(), [1, 2, 4]);
+    }
+
+    #[test]
+    fn eliminate_ignores_out_of_range() {
+        let mut s = Sudoku::new(4);
+        assert!(!s.eliminate(4, 0, 1));
+        assert!(!s.eliminate(0, 0, 0));
+        assert!(!s.eliminate(0, 0, 5));
+        assert_eq!(s.candidate_count(), 64);
+    }
+
+    #[test]
+    fn supports_sizes_up_to_64() {
+        let mut s = Sudoku::new(64);
+        assert_eq!(s.candidates(63, 63), Candidates::all(64));
+        s.set(63, 0, 64).unwrap();
+        assert_masks_in_sync(&s);
+        assert!(!s.candidates(63, 63).contains(64));
+        assert!(s.candidates(0, 63).contains(64));
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 64")]
+    fn rejects_sizes_above_64() {
+        Sudoku::new(81);
+    }
+}
@@ -1,20 +1,27 @@
+use crate::candidates::Candidates;
+use crate::rating::{Rating, Thresholds};
 use crate::sodo::{Cell, Sudoku};
-use crate::strategy::{Strategy, all as all_strategies};
-use rand::{Rng, rng, seq::SliceRandom};
+use crate::step::Step;
+use crate::strategy::{self, Strategy, all as all_strategies};
+use rand::{Rng, SeedableRng, seq::SliceRandom};
+use rand_chacha::ChaCha8Rng;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 /// Statistics collected during solving.
 #[derive(Debug, Clone, Default)]
 pub struct Stats {
     pub strategies_used: HashMap<String, usize>,
     pub cells_filled: usize,
+    pub candidates_eliminated: usize,
     pub iterations: usize,
     pub backtracks: usize,
 }
 
-/// Puzzle difficulty level.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Puzzle difficulty level, from easiest to hardest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Difficulty {
+    #[default]
     Easy,
     Medium,
     Hard,
@@ -25,7 +32,12 @@ pub enum Difficulty {
 pub struct Solver {
     strategies: Vec<Box<dyn Strategy>>,
     max_iters: usize,
+    max_attempts: usize,
+    time_limit: Option<Duration>,
+    thresholds: Option<Thresholds>,
     backtrack: bool,
+    uniqueness: bool,
+    rng: ChaCha8Rng,
 }
 
 impl Default for Solver {
@@ -39,32 +51,113 @@ impl Solver {
     pub fn new() -> Self {
         Self {
             strategies: all_strategies(),
-            max_iters: 1000,
+            max_iters: 10_000,
+            max_attempts: 100,
+            time_limit: None,
+            thresholds: None,
             backtrack: true,
+            uniqueness: false,
+            rng: ChaCha8Rng::from_os_rng(),
         }
     }
 
-    /// Creates a solver with custom strategies.
+    /// Creates a solver with custom strategies. Steps are always taken from
+    /// the easiest strategy that has one, by registry difficulty; strategies
+    /// outside the registry count as easiest.
     pub fn with_strategies(strategies: Vec<Box<dyn Strategy>>) -> Self {
         Self {
             strategies,
-            max_iters: 1000,
+            max_iters: 10_000,
+            max_attempts: 100,
+            time_limit: None,
+            thresholds: None,
             backtrack: true,
+            uniqueness: false,
+            rng: ChaCha8Rng::from_os_rng(),
         }
     }
 
-    /// Sets maximum iterations for logical solving.
+    /// Creates a solver with the registered strategies named by `ids`, such as
+    /// `["naked-single", "x-wing"]`.
+    pub fn from_ids<S: AsRef<str>>(ids: &[S]) -> Result<Self, String> {
+        strategy::by_ids(ids).map(Self::with_strategies)
+    }
+
+    /// Sets the maximum number of logical steps taken before giving up.
     pub fn max_iterations(mut self, n: usize) -> Self {
         self.max_iters = n;
         self
     }
 
+    /// Sets how many complete grids [`generate`](Self::generate) may try
+    /// before giving up.
+    pub fn max_attempts(mut self, n: usize) -> Self {
+        self.max_attempts = n;
+        self
+    }
+
+    /// Sets how long [`generate`](Self::generate) may run before giving up,
+    /// checked between attempts. Unlimited by default. Not supported on
+    /// `wasm32-unknown-unknown`, which has no clock.
+    pub fn time_limit(mut self, limit: Duration) -> Self {
+        self.time_limit = Some(limit);
+        self
+    }
+
+    /// Seeds the random generator used by [`generate`](Self::generate), so
+    /// the same seed gives the same puzzles on every run and platform.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+        self
+    }
+
+    /// Sets the score thresholds [`generate`](Self::generate) classifies
+    /// puzzles with; see [`Rating::difficulty_with`]. By default they are
+    /// scaled to the size generated, as [`Thresholds::for_size`] does.
+    pub fn thresholds(mut self, thresholds: Thresholds) -> Self {
+        self.thresholds = Some(thresholds);
+        self
+    }
+
     /// Enables or disables backtracking.
     pub fn use_backtracking(mut self, enabled: bool) -> Self {
         self.backtrack = enabled;
         self
     }
 
+    /// Enables or disables strategies that assume the puzzle has a unique
+    /// solution, such as Unique Rectangles. Off by default: on a puzzle with
+    /// several solutions they can eliminate correct candidates.
+    pub fn use_uniqueness(mut self, enabled: bool) -> Self {
+        self.uniqueness = enabled;
+        self
+    }
+
+    /// Iterates over the strategies in use, in priority order.
+    fn active(&self) -> impl Iterator<Item = &dyn Strategy> {
+        self.strategies
+            .iter()
+            .map(|s| s.as_ref())
+            .filter(|s| self.uniqueness || !s.requires_uniqueness())
+    }
+
+    /// Returns the strategies in use, easiest first.
+    fn ranked(&self) -> Vec<(f32, &dyn Strategy)> {
+        Self::by_cost(self.active())
+    }
+
+    /// Pairs each strategy with the lowest difficulty of its steps and sorts
+    /// them by it, keeping the given order between equals.
+    fn by_cost<'a>(
+        strategies: impl Iterator<Item = &'a dyn Strategy>,
+    ) -> Vec<(f32, &'a dyn Strategy)> {
+        let mut ranked: Vec<_> = strategies
+            .map(|s| (strategy::base_difficulty(s.name()), s))
+            .collect();
+        ranked.sort_by(|a, b| a.0.total_cmp(&b.0));
+        ranked
+    }
+
     /// Solves the puzzle, returning the solution.
     pub fn solve(&mut self, sudoku: Sudoku) -> Result<Sudoku, String> {
         self.solve_with_stats(sudoku).map(|(s, _)| s)
@@ -93,40 +186,53 @@ impl Solver {
         }
     }
 
+    /// Applies one step at a time, always restarting from the simplest
+    /// strategy, until the puzzle is complete or no strategy makes progress.
     fn apply_strategies(&self, sudoku: &mut Sudoku, stats: &mut Stats) -> bool {
-        let mut progress = true;
+        let strategies = self.ranked();
 
-        while progress && !sudoku.is_complete() && stats.iterations < self.max_iters {
-            progress = false;
+        while !sudoku.is_complete() && stats.iterations < self.max_iters {
+            let Some((step, _, found_by)) = Self::easiest_step(sudoku, &strategies) else {
+                break;
+            };
             stats.iterations += 1;
+            if !Self::apply_step(sudoku, &step, found_by.name(), stats) {
+                break;
+            }
 
-            for strategy in &self.strategies {
-                let before = sudoku.empty_count();
-
-                if strategy.apply(sudoku) {
-                    stats.cells_filled += before - sudoku.empty_count();
-                    *stats
-                        .strategies_used
-                        .entry(strategy.name().into())
-                        .or_default() += 1;
-                    progress = true;
-
-                    if !sudoku.is_valid() {
-                        return false;
-                    }
-                }
+            if !sudoku.is_valid() {
+                return false;
             }
         }
 
         sudoku.is_solved()
     }
 
+    /// Applies a step and records it in the statistics under the name of the
+    /// strategy that found it, since steps may name a variant such as a
+    /// Sashimi X-Wing. Returns true if progress was made.
+    fn apply_step(sudoku: &mut Sudoku, step: &Step, strategy: &str, stats: &mut Stats) -> bool {
+        let before = sudoku.empty_count();
+        let eliminated = step
+            .eliminations
+            .iter()
+            .filter(|&&(r, c, v)| sudoku.candidates(r, c).contains(v))
+            .count();
+
+        let progress = step.apply(sudoku);
+
+        stats.cells_filled += before - sudoku.empty_count();
+        stats.candidates_eliminated += eliminated;
+        *stats.strategies_used.entry(strategy.into()).or_default() += 1;
+        progress
+    }
+
     fn backtrack_solve(&self, sudoku: &mut Sudoku, stats: &mut Stats) -> bool {
         if sudoku.is_complete() {
             return sudoku.is_valid();
         }
 
-        let Some((r, c)) = self.find_mrv_cell(sudoku) else {
+        let Some((r, c)) = Self::find_mrv_cell(sudoku) else {
             return sudoku.is_valid();
         };
 
@@ -146,7 +252,7 @@ impl Solver {
     }
 
     /// Finds empty cell with minimum remaining values (MRV heuristic).
-    fn find_mrv_cell(&self, sudoku: &Sudoku) -> Option<(usize, usize)> {
+    fn find_mrv_cell(sudoku: &Sudoku) -> Option<(usize, usize)> {
         let mut best = None;
         let mut min_cands = usize::MAX;
 
@@ -169,42 +275,129 @@ impl Solver {
     }
 
     /// Returns a hint: (row, col, value) for the next logical move.
+    ///
+    /// Elimination-only steps are applied to a scratch copy until a
+    /// placement turns up.
     pub fn hint(&self, sudoku: &Sudoku) -> Option<(usize, usize, u8)> {
-        // Try naked singles first
-        for r in 0..sudoku.size {
-            for c in 0..sudoku.size {
-                if sudoku.grid[r][c].is_empty() {
-                    let cands = sudoku.candidates(r, c);
-                    if cands.len() == 1 {
-                        return Some((r, c, *cands.iter().next().unwrap()));
-                    }
-                }
+        let mut temp = sudoku.clone();
+
+        for _ in 0..self.max_iters {
+            let step = self.next_step(&temp)?;
+            if let Some(&placement) = step.placements.first() {
+                return Some(placement);
             }
+            step.apply(&mut temp);
         }
 
-        // Try hidden singles
-        for strategy in &self.strategies {
-            if strategy.name() == "Hidden Singles" {
-                let mut temp = sudoku.clone();
-                if strategy.apply(&mut temp) {
-                    for r in 0..sudoku.size {
-                        for c in 0..sudoku.size {
-                            if sudoku.grid[r][c] != temp.grid[r][c]
-                                && let Some(v) = temp.grid[r][c].value()
-                            {
-                                return Some((r, c, v));
-                            }
-                        }
-                    }
+        None
+    }
+
+    /// Solves the puzzle logically, returning every deduction in order.
+    ///
+    /// Stops early if the strategies get stuck; backtracking is never used.
+    pub fn explain(&self, sudoku: &Sudoku) -> Vec<Step> {
+        let mut temp = sudoku.clone();
+        let mut steps = Vec::new();
+
+        while !temp.is_complete() && steps.len() < self.max_iters {
+            let Some(step) = self.next_step(&temp) else {
+                break;
+            };
+            if !step.apply(&mut temp) {
+                break;
+            }
+            steps.push(step);
+        }
+
+        steps
+    }
+
+    /// Rates the puzzle by solving it logically, always taking the easiest
+    /// step available as Sudoku Explainer does.
+    ///
+    /// Returns `None` if the strategies get stuck before the puzzle is solved.
+    pub fn rate(&self, sudoku: &Sudoku) -> Option<Rating> {
+        self.rate_with(sudoku, &self.active().collect::<Vec<_>>())
+    }
+
+    /// Rates the puzzle using only the given strategies.
+    fn rate_with(&self, sudoku: &Sudoku, strategies: &[&dyn Strategy]) -> Option<Rating> {
+        let strategies = Self::by_cost(strategies.iter().copied());
+        let mut temp = sudoku.clone();
+        let mut rating: Option<Rating> = None;
+        let mut placed = false;
+
+        for _ in 0..self.max_iters {
+            if temp.is_complete() {
+                break;
+            }
+            let (step, difficulty, found_by) = Self::easiest_step(&temp, &strategies)?;
+            if !step.apply(&mut temp) {
+                return None;
+            }
+
+            let r = rating.get_or_insert(Rating {
+                ep: difficulty,
+                ..Rating::default()
+            });
+            r.er = r.er.max(difficulty);
+            if let Some(info) = strategy::lookup_name(found_by.name()) {
+                r.score += info.score;
+                r.level = r.level.max(info.level);
+            }
+            if !placed {
+                r.ed = r.ed.max(difficulty);
+                placed = !step.placements.is_empty();
+            }
+        }
+
+        temp.is_solved().then(|| Rating {
+            size: sudoku.size,
+            ..rating.unwrap_or_default()
+        })
+    }
+
+    /// Finds the step with the lowest difficulty and the strategy finding it,
+    /// given strategies sorted by the lowest difficulty of their steps.
+    fn easiest_step<'a>(
+        sudoku: &Sudoku,
+        strategies: &[(f32, &'a dyn Strategy)],
+    ) -> Option<(Step, f32, &'a dyn Strategy)> {
+        let mut best: Option<(Step, f32, &dyn Strategy)> = None;
+
+        for &(base, strategy) in strategies {
+            if best.as_ref().is_some_and(|&(_, d, _)| base >= d) {
+                break;
+            }
+            if let Some(step) = strategy.find_step(sudoku) {
+                let difficulty = strategy.difficulty(&step);
+                if best.as_ref().is_none_or(|&(_, d, _)| difficulty < d) {
+                    best = Some((step, difficulty, strategy));
                 }
             }
         }
 
-        None
+        best
     }
 
-    /// Counts solutions up to a maximum.
-    pub fn count_solutions(&self, mut sudoku: Sudoku, max: usize) -> usize {
+    /// Returns the easiest step available, trying strategies from the lowest
+    /// difficulty up. This is the step [`rate`](Self::rate) takes too.
+    pub fn next_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        Self::easiest_step(sudoku, &self.ranked()).map(|(step, ..)| step)
+    }
+
+    /// Counts solutions up to a maximum. Eliminated candidates are ignored.
+    pub fn count_solutions(&self, sudoku: Sudoku, max: usize) -> usize {
+        Self::count(sudoku, max)
+    }
+
+    /// Counts solutions up to a maximum, without needing a solver.
+    pub(crate) fn count(mut sudoku: Sudoku, max: usize) -> usize {
+        if !sudoku.is_valid() {
+            return 0;
+        }
+
+        sudoku.reset_candidates();
         let mut count = 0;
         Self::count_recursive(&mut sudoku, &mut count, max);
         count
@@ -215,68 +408,169 @@ impl Solver {
             return;
         }
 
-        if sudoku.is_complete() {
-            if sudoku.is_valid() {
-                *count += 1;
-            }
+        // Only candidates are placed, so a valid grid stays valid.
+        let Some(branches) = Self::branches(sudoku) else {
+            *count += 1;
             return;
+        };
+
+        for (r, c, val) in branches {
+            sudoku.put(r, c, Cell::Filled(val));
+            Self::count_recursive(sudoku, count, max);
+            sudoku.put(r, c, Cell::Empty);
         }
+    }
 
-        let Some((r, c)) = sudoku.first_empty() else {
-            return;
-        };
+    /// Returns the placements to branch on: the candidates of the emptiest
+    /// cell, or the places left for a digit in a unit if there are fewer.
+    /// Returns `None` once the grid is full.
+    fn branches(sudoku: &Sudoku) -> Option<Vec<(usize, usize, u8)>> {
+        let (row, col) = Self::find_mrv_cell(sudoku)?;
+        let (n, bs) = (sudoku.size, sudoku.box_size);
+        let mut fewest = sudoku.candidates(row, col).len();
+        let mut pick = None;
+
+        for unit in strategy::units(n) {
+            if fewest <= 1 {
+                break;
+            }
 
-        for val in sudoku.candidates(r, c) {
-            if sudoku.set(r, c, val).is_ok() && sudoku.is_valid() {
-                Self::count_recursive(sudoku, count, max);
+            let mut placed = Candidates::EMPTY;
+            let mut places = [0; 65];
+            for (r, c) in unit.cells(bs) {
+                match sudoku.grid[r][c].value() {
+                    Some(v) => placed.insert(v),
+                    None => sudoku
+                        .candidates(r, c)
+                        .iter()
+                        .for_each(|v| places[v as usize] += 1),
+                }
+            }
+
+            for v in Candidates::all(n) - placed {
+                if places[v as usize] < fewest {
+                    fewest = places[v as usize];
+                    pick = Some((unit, v));
+                }
             }
-            let _ = sudoku.set(r, c, 0);
         }
+
+        Some(match pick {
+            Some((unit, v)) => unit
+                .cells(bs)
+                .filter(|&(r, c)| sudoku.candidates(r, c).contains(v))
+                .map(|(r, c)| (r, c, v))
+                .collect(),
+            None => sudoku
+                .candidates(row, col)
+                .iter()
+                .map(|v| (row, col, v))
+                .collect(),
+        })
     }
 
-    /// Generates a puzzle of the given size and difficulty.
+    /// Generates a puzzle of the given size that rates at the given
+    /// difficulty level, as [`Rating::difficulty_with`] classifies it with the
+    /// solver's [`thresholds`](Self::thresholds).
+    ///
+    /// Givens are removed from a random complete grid, in symmetric pairs,
+    /// for as long as the puzzle still solves with the strategies at or below
+    /// that level. Grids that end up rated too easy or too hard are thrown
+    /// away, up to the budget set by [`max_attempts`](Self::max_attempts)
+    /// and [`time_limit`](Self::time_limit).
+    ///
+    /// Grids smaller than 9x9 are too constrained to need anything beyond
+    /// singles, so only [`Difficulty::Easy`] is available for them; other
+    /// levels fail right away.
+    ///
+    /// The puzzle always has a unique solution: no removal is kept unless a
+    /// search for a second solution fails.
     pub fn generate(&mut self, size: usize, difficulty: Difficulty) -> Result<Sudoku, String> {
+        let mut rng = self.rng.clone();
+        let result = self.generate_with_rng(size, difficulty, &mut rng);
+        self.rng = rng;
+        result
+    }
+
+    /// Generates a puzzle like [`generate`](Self::generate), drawing from
+    /// the given random generator instead of the solver's own.
+    pub fn generate_with_rng<R: Rng + ?Sized>(
+        &self,
+        size: usize,
+        difficulty: Difficulty,
+        rng: &mut R,
+    ) -> Result<Sudoku, String> {
+        if size < 9 && difficulty > Difficulty::Easy {
+            return Err(format!(
+                "{difficulty:?} puzzles are not available at size {size}; only Easy is"
+            ));
+        }
+
+        let thresholds = self
+            .thresholds
+            .unwrap_or_else(|| Thresholds::for_size(size));
+        let deadline = self.time_limit.map(|limit| (limit, Instant::now() + limit));
+        let tier: Vec<_> = self
+            .active()
+            .filter(|s| strategy::lookup_name(s.name()).is_none_or(|info| info.level <= difficulty))
+            .collect();
+
+        for _ in 0..self.max_attempts {
+            if let Some((limit, deadline)) = deadline
+                && Instant::now() >= deadline
+            {
+                return Err(format!("No {difficulty:?} puzzle found within {limit:?}"));
+            }
+
+            // Filling the diagonal boxes first can leave no way to complete
+            // a small grid.
+            let Some(solution) = self.complete_grid(size, rng) else {
+                continue;
+            };
+            let puzzle = self.dig(solution, &tier, rng);
+
+            // Rate with every strategy, as `rate` does: an easier step from
+            // a harder tier can change the classification.
+            if self
+                .rate(&puzzle)
+                .is_some_and(|rating| rating.difficulty_with(&thresholds) == difficulty)
+            {
+                return Ok(puzzle);
+            }
+        }
+
+        Err(format!(
+            "No {difficulty:?} puzzle found in {} attempts",
+            self.max_attempts
+        ))
+    }
+
+    /// Fills a random complete grid.
+    fn complete_grid<R: Rng + ?Sized>(&self, size: usize, rng: &mut R) -> Option<Sudoku> {
         let mut sudoku = Sudoku::new(size);
-        let mut rng = rng();
 
         // Fill diagonal boxes first (they don't affect each other)
         let bs = sudoku.box_size;
         let mut diag: Vec<usize> = (0..bs).collect();
-        diag.shuffle(&mut rng);
+        diag.shuffle(rng);
 
         for &i in &diag {
-            self.fill_box(&mut sudoku, i * bs)?;
+            self.fill_box(&mut sudoku, i * bs, rng).ok()?;
         }
 
-        // Solve to get complete grid
-        let solution = self.solve(sudoku.clone())?;
-
-        // Calculate cells to remove based on difficulty
-        let total = size * size;
-        let base_remove = match difficulty {
-            Difficulty::Easy => total * 40 / 100,
-            Difficulty::Medium => total * 50 / 100,
-            Difficulty::Hard => total * 60 / 100,
-            Difficulty::Expert => total * 70 / 100,
-        };
-
-        // Add ±5% variation
-        let var = (base_remove as f32 * 0.05) as usize;
-        let to_remove = if var > 0 {
-            let min = base_remove.saturating_sub(var);
-            let max = (base_remove + var).min(total - 17);
-            rng.random_range(min..=max)
-        } else {
-            base_remove
-        };
-
-        self.remove_cells(solution, to_remove)
+        self.backtrack_solve(&mut sudoku, &mut Stats::default())
+            .then_some(sudoku)
     }
 
-    fn fill_box(&self, sudoku: &mut Sudoku, start: usize) -> Result<(), String> {
+    fn fill_box<R: Rng + ?Sized>(
+        &self,
+        sudoku: &mut Sudoku,
+        start: usize,
+        rng: &mut R,
+    ) -> Result<(), String> {
         let bs = sudoku.box_size;
         let mut vals: Vec<u8> = (1..=sudoku.size as u8).collect();
-        vals.shuffle(&mut rng());
+        vals.shuffle(rng);
 
         let mut i = 0;
         for r in start..start + bs {
@@ -289,41 +583,41 @@ impl Solver {
         Ok(())
     }
 
-    fn remove_cells(&self, mut sudoku: Sudoku, to_remove: usize) -> Result<Sudoku, String> {
+    /// Removes givens in random symmetric pairs, keeping each removal only if
+    /// the puzzle still solves with `strategies`.
+    fn dig<R: Rng + ?Sized>(
+        &self,
+        mut sudoku: Sudoku,
+        strategies: &[&dyn Strategy],
+        rng: &mut R,
+    ) -> Sudoku {
         let size = sudoku.size;
-        let mut rng = rng();
-        let mut removed = 0;
+        let mirror = |(r, c): (usize, usize)| (size - 1 - r, size - 1 - c);
 
         let mut positions: Vec<_> = (0..size)
             .flat_map(|r| (0..size).map(move |c| (r, c)))
+            .filter(|&cell| cell <= mirror(cell))
             .collect();
-        positions.shuffle(&mut rng);
+        positions.shuffle(rng);
 
-        for (r, c) in positions {
-            if removed >= to_remove {
-                break;
+        for cell in positions {
+            let mut candidate = sudoku.clone();
+            for (r, c) in [cell, mirror(cell)] {
+                candidate.put(r, c, Cell::Empty);
             }
 
-            if sudoku.grid[r][c] != Cell::Empty {
-                sudoku.grid[r][c] = Cell::Empty;
-                removed += 1;
-
-                // Remove symmetric cell with 70% probability
-                if removed < to_remove && rng.random_bool(0.7) {
-                    let (sr, sc) = (size - 1 - r, size - 1 - c);
-                    if (sr != r || sc != c) && sudoku.grid[sr][sc] != Cell::Empty {
-                        sudoku.grid[sr][sc] = Cell::Empty;
-                        removed += 1;
-                    }
-                }
+            // Counting is much cheaper than finding the strategies stuck.
+            if candidate.has_unique_solution() && self.rate_with(&candidate, strategies).is_some() {
+                sudoku = candidate;
             }
         }
 
-        Ok(sudoku)
+        sudoku
     }
 
-    /// Applies one strategy step. Returns true if progress was made.
+    /// Applies the easiest step available. Returns true if progress was made.
     pub fn step(&self, sudoku: &mut Sudoku) -> bool {
-        self.strategies.iter().any(|s| s.apply(sudoku))
+        self.next_step(sudoku)
+            .is_some_and(|step| step.apply(sudoku))
     }
 }
@@ -0,0 +1,304 @@
+use crate::candidates::Candidates;
+use crate::chain::Chain;
+use crate::forcing::Branch;
+use crate::sodo::Sudoku;
+use std::fmt;
+
+/// A row, column or box of the grid. Indices are zero-based; boxes are
+/// counted row-major from the top-left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Row(usize),
+    Col(usize),
+    Box(usize),
+}
+
+impl Unit {
+    /// Returns the `i`-th cell of the unit.
+    #[inline]
+    pub fn cell(self, i: usize, box_size: usize) -> (usize, usize) {
+        match self {
+            Self::Row(r) => (r, i),
+            Self::Col(c) => (i, c),
+            Self::Box(b) => (
+                b / box_size * box_size + i / box_size,
+                b % box_size * box_size + i % box_size,
+            ),
+        }
+    }
+
+    /// Iterates over the cells of the unit.
+    pub fn cells(self, box_size: usize) -> impl Iterator<Item = (usize, usize)> {
+        (0..box_size * box_size).map(move |i| self.cell(i, box_size))
+    }
+
+    /// Returns true if the unit contains (row, col).
+    #[inline]
+    pub fn contains(self, row: usize, col: usize, box_size: usize) -> bool {
+        match self {
+            Self::Row(r) => r == row,
+            Self::Col(c) => c == col,
+            Self::Box(b) => row / box_size * box_size + col / box_size == b,
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Row(i) => write!(f, "row {}", i + 1),
+            Self::Col(i) => write!(f, "column {}", i + 1),
+            Self::Box(i) => write!(f, "box {}", i + 1),
+        }
+    }
+}
+
+/// A single logical deduction found by a [`Strategy`](crate::Strategy).
+///
+/// A step lists what it concludes (placements and eliminations) together with
+/// the pattern that justifies it: the units, cells and digits involved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// Name of the technique, e.g. "Hidden Single".
+    pub strategy: &'static str,
+    /// Values to place, as (row, col, value).
+    pub placements: Vec<(usize, usize, u8)>,
+    /// Candidates to remove, as (row, col, value).
+    pub eliminations: Vec<(usize, usize, u8)>,
+    /// Units forming the pattern.
+    pub units: Vec<Unit>,
+    /// Cells forming the pattern.
+    pub cells: Vec<(usize, usize)>,
+    /// Digits forming the pattern.
+    pub digits: Candidates,
+    /// Technique-specific roles within the pattern.
+    pub detail: Detail,
+}
+
+/// Technique-specific parts of a [`Step`]'s pattern, for highlighting.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Detail {
+    #[default]
+    None,
+    /// Fin cells of a finned or sashimi fish.
+    Fins(Vec<(usize, usize)>),
+    /// Pivot and pincer cells of a wing. The pivot of a W-Wing is the pair of
+    /// cells forming its strong link.
+    Wing {
+        pivot: Vec<(usize, usize)>,
+        pincers: Vec<(usize, usize)>,
+    },
+    /// Conjugate-pair clusters, each split into its two color classes.
+    Coloring(Vec<[Vec<(usize, usize)>; 2]>),
+    /// The chain behind the deduction.
+    Chain(Chain),
+    /// Almost locked sets joined by their restricted common digits, and the
+    /// stem cell of a Death Blossom.
+    Als {
+        stem: Option<(usize, usize)>,
+        sets: Vec<Vec<(usize, usize)>>,
+        restricted: Candidates,
+    },
+    /// The branches of a forcing chain or Nishio, one per assumption.
+    Forcing(Vec<Branch>),
+}
+
+impl Step {
+    /// Creates an empty step for the named technique.
+    pub fn new(strategy: &'static str) -> Self {
+        Self {
+            strategy,
+            placements: Vec::new(),
+            eliminations: Vec::new(),
+            units: Vec::new(),
+            cells: Vec::new(),
+            digits: Candidates::EMPTY,
+            detail: Detail::None,
+        }
+    }
+
+    /// Adds a placement.
+    pub fn place(mut self, row: usize, col: usize, val: u8) -> Self {
+        self.placements.push((row, col, val));
+        self
+    }
+
+    /// Adds an elimination.
+    pub fn eliminate(mut self, row: usize, col: usize, val: u8) -> Self {
+        self.eliminations.push((row, col, val));
+        self
+    }
+
+    /// Adds a unit to the pattern.
+    pub fn unit(mut self, unit: Unit) -> Self {
+        self.units.push(unit);
+        self
+    }
+
+    /// Adds a cell to the pattern.
+    pub fn cell(mut self, row: usize, col: usize) -> Self {
+        self.cells.push((row, col));
+        self
+    }
+
+    /// Adds a digit to the pattern.
+    pub fn digit(mut self, val: u8) -> Self {
+        self.digits.insert(val);
+        self
+    }
+
+    /// Returns true if the step places at least one value.
+    #[inline]
+    pub fn is_placement(&self) -> bool {
+        !self.placements.is_empty()
+    }
+
+    /// Applies the step to a puzzle. Returns true if anything changed.
+    pub fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        let mut progress = false;
+
+        for &(r, c, v) in &self.placements {
+            if sudoku.grid[r][c].is_empty() && sudoku.set(r, c, v).is_ok() {
+                progress = true;
+            }
+        }
+
+        for &(r, c, v) in &self.eliminations {
+            progress |= sudoku.eliminate(r, c, v);
+        }
+
+        progress
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.strategy)?;
+
+        match (self.placements.as_slice(), self.units.as_slice()) {
+            _ if matches!(self.detail, Detail::Chain(_) | Detail::Forcing(_)) => {}
+            (&[(r, c, v)], &[unit]) => {
+                return write!(f, "{v} in {unit} must go at {}", Pos(r, c));
+            }
+            (&[(r, c, v)], []) => return write!(f, "{} can only be {v}", Pos(r, c)),
+            _ => {}
+        }
+
+        let mut pattern = Vec::new();
+        if let Detail::Chain(chain) = &self.detail {
+            pattern.push(chain.to_string());
+        } else if let Detail::Forcing(branches) = &self.detail {
+            let branches: Vec<_> = branches.iter().map(Branch::to_string).collect();
+            pattern.push(branches.join(" | "));
+        } else {
+            if !self.digits.is_empty() {
+                let digits: Vec<_> = self.digits.iter().map(|d| d.to_string()).collect();
+                pattern.push(digits.join("/"));
+            }
+            if !self.units.is_empty() {
+                let units: Vec<_> = self.units.iter().map(Unit::to_string).collect();
+                pattern.push(format!("in {}", units.join(", ")));
+            }
+        }
+
+        match &self.detail {
+            Detail::None if !self.cells.is_empty() => {
+                pattern.push(format!("({})", cells(&self.cells)));
+            }
+            Detail::None | Detail::Chain(_) | Detail::Forcing(_) => {}
+            Detail::Fins(fins) => {
+                pattern.push(format!("({}) fins ({})", cells(&self.cells), cells(fins)));
+            }
+            Detail::Wing { pivot, pincers } => {
+                pattern.push(format!(
+                    "pivot ({}) pincers ({})",
+                    cells(pivot),
+                    cells(pincers)
+                ));
+            }
+            Detail::Coloring(clusters) => {
+                for [on, off] in clusters {
+                    pattern.push(format!("colors ({}) / ({})", cells(on), cells(off)));
+                }
+            }
+            Detail::Als {
+                stem,
+                sets,
+                restricted,
+            } => {
+                if let &Some((r, c)) = stem {
+                    pattern.push(format!("stem {}", Pos(r, c)));
+                }
+                let sets: Vec<_> = sets.iter().map(|set| format!("({})", cells(set))).collect();
+                let restricted: Vec<_> = restricted.iter().map(|d| d.to_string()).collect();
+                pattern.push(format!(
+                    "sets {} via {}",
+                    sets.join(" "),
+                    restricted.join("/")
+                ));
+            }
+        }
+        write!(f, "{}", pattern.join(" "))?;
+
+        let conclusions: Vec<_> = self
+            .placements
+            .iter()
+            .map(|&(r, c, v)| format!("{}={v}", Pos(r, c)))
+            .chain(
+                self.eliminations
+                    .iter()
+                    .map(|&(r, c, v)| format!("{}<>{v}", Pos(r, c))),
+            )
+            .collect();
+        write!(f, " => {}", conclusions.join(", "))
+    }
+}
+
+/// Formats a list of cells, space separated.
+fn cells(cells: &[(usize, usize)]) -> String {
+    let cells: Vec<_> = cells.iter().map(|&(r, c)| Pos(r, c).to_string()).collect();
+    cells.join(" ")
+}
+
+/// Formats a cell position in one-based `r1c1` notation.
+pub(crate) struct Pos(pub usize, pub usize);
+
+impl fmt::Display for Pos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "r{}c{}", self.0 + 1, self.1 + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_places_and_eliminates() {
+        let mut sudoku = Sudoku::new(4);
+        let step = Step::new("Test").place(0, 0, 1).eliminate(1, 1, 2);
+
+        assert!(step.apply(&mut sudoku));
+        assert_eq!(sudoku.get(0, 0).and_then(|c| c.value()), Some(1));
+        assert!(!sudoku.candidates(1, 1).contains(2));
+        assert!(!sudoku.candidates(1, 1).contains(1), "1 is in the box");
+    }
+
+    #[test]
+    fn apply_reports_no_progress_when_repeated() {
+        let mut sudoku = Sudoku::new(4);
+        let step = Step::new("Test").place(0, 0, 1).eliminate(3, 3, 4);
+
+        assert!(step.apply(&mut sudoku));
+        assert!(!step.apply(&mut sudoku));
+    }
+
+    #[test]
+    fn apply_skips_filled_cells() {
+        let mut sudoku = Sudoku::new(4);
+        sudoku.set(0, 0, 3).unwrap();
+
+        assert!(!Step::new("Test").place(0, 0, 1).apply(&mut sudoku));
+        assert_eq!(sudoku.get(0, 0).and_then(|c| c.value()), Some(3));
+    }
+}
@@ -1,17 +1,465 @@
-use crate::Sudoku;
+//! Logical solving strategies.
+//!
+//! Each strategy can be used on its own through [`Solver::with_strategies`],
+//! or picked by its identifier from the [`registry`] with [`Solver::from_ids`].
+//!
+//! [`Solver::with_strategies`]: crate::Solver::with_strategies
+//! [`Solver::from_ids`]: crate::Solver::from_ids
+
+use crate::candidates::Candidates;
+use crate::chain::{Deduction, Graph, LoopKind, Rules};
+use crate::forcing::{Branch, Propagation};
+use crate::step::{Detail, Step, Unit};
+use crate::{Difficulty, Sudoku};
 
 /// A solving strategy that can make progress on a puzzle.
 pub trait Strategy: Send + Sync {
-    /// Attempts to apply the strategy. Returns true if progress was made.
-    fn apply(&self, sudoku: &mut Sudoku) -> bool;
+    /// Finds the next deduction this strategy can make, without modifying the puzzle.
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step>;
 
     /// Returns the strategy name.
     fn name(&self) -> &'static str;
+
+    /// Returns the difficulty of a step found by this strategy, on the Sudoku
+    /// Explainer scale. Defaults to the registry weight for the strategy's
+    /// name, or 0.0 if it is not registered.
+    fn difficulty(&self, _step: &Step) -> f32 {
+        base_difficulty(self.name())
+    }
+
+    /// Returns true if the strategy assumes the puzzle has a unique solution.
+    fn requires_uniqueness(&self) -> bool {
+        false
+    }
+
+    /// Finds and applies one step. Returns true if progress was made.
+    fn apply(&self, sudoku: &mut Sudoku) -> bool {
+        self.find_step(sudoku)
+            .is_some_and(|step| step.apply(sudoku))
+    }
+}
+
+/// Registry entry describing a built-in strategy.
+#[derive(Debug, Clone, Copy)]
+pub struct Info {
+    /// Stable identifier, e.g. `"x-wing"`.
+    pub id: &'static str,
+    /// Display name, e.g. `"X-Wing"`.
+    pub name: &'static str,
+    /// Difficulty weight on the Sudoku Explainer scale, from about 1.0 to 10.0.
+    pub difficulty: f32,
+    /// Score added for each step, on the HoDoKu scale.
+    pub score: u32,
+    /// Lowest level of puzzle that may need this strategy.
+    pub level: Difficulty,
+    make: fn() -> Box<dyn Strategy>,
+}
+
+impl Info {
+    /// Creates the strategy with its default settings.
+    pub fn build(&self) -> Box<dyn Strategy> {
+        (self.make)()
+    }
+}
+
+/// Every built-in strategy, in priority order: by difficulty, easiest first.
+static REGISTRY: &[Info] = &[
+    Info {
+        id: "hidden-single",
+        name: "Hidden Single",
+        difficulty: 1.2,
+        score: 14,
+        level: Difficulty::Easy,
+        make: || Box::new(HiddenSingles),
+    },
+    Info {
+        id: "naked-single",
+        name: "Naked Single",
+        difficulty: 2.3,
+        score: 4,
+        level: Difficulty::Easy,
+        make: || Box::new(NakedSingles),
+    },
+    Info {
+        id: "pointing",
+        name: "Pointing",
+        difficulty: 2.6,
+        score: 50,
+        level: Difficulty::Medium,
+        make: || Box::new(Pointing),
+    },
+    Info {
+        id: "claiming",
+        name: "Claiming",
+        difficulty: 2.8,
+        score: 50,
+        level: Difficulty::Medium,
+        make: || Box::new(Claiming),
+    },
+    Info {
+        id: "naked-pair",
+        name: "Naked Pair",
+        difficulty: 3.0,
+        score: 60,
+        level: Difficulty::Medium,
+        make: || Box::new(NakedSubset::new(2)),
+    },
+    Info {
+        id: "x-wing",
+        name: "X-Wing",
+        difficulty: 3.2,
+        score: 140,
+        level: Difficulty::Hard,
+        make: || Box::new(Fish::new(2)),
+    },
+    Info {
+        id: "hidden-pair",
+        name: "Hidden Pair",
+        difficulty: 3.4,
+        score: 70,
+        level: Difficulty::Medium,
+        make: || Box::new(HiddenSubset::new(2)),
+    },
+    Info {
+        id: "finned-x-wing",
+        name: "Finned X-Wing",
+        difficulty: 3.4,
+        score: 130,
+        level: Difficulty::Hard,
+        make: || Box::new(FinnedFish::new(2)),
+    },
+    Info {
+        id: "naked-triple",
+        name: "Naked Triple",
+        difficulty: 3.6,
+        score: 80,
+        level: Difficulty::Medium,
+        make: || Box::new(NakedSubset::new(3)),
+    },
+    Info {
+        id: "swordfish",
+        name: "Swordfish",
+        difficulty: 3.8,
+        score: 150,
+        level: Difficulty::Hard,
+        make: || Box::new(Fish::new(3)),
+    },
+    Info {
+        id: "skyscraper",
+        name: "Skyscraper",
+        difficulty: 4.0,
+        score: 130,
+        level: Difficulty::Hard,
+        make: || Box::new(Skyscraper),
+    },
+    Info {
+        id: "hidden-triple",
+        name: "Hidden Triple",
+        difficulty: 4.0,
+        score: 100,
+        level: Difficulty::Medium,
+        make: || Box::new(HiddenSubset::new(3)),
+    },
+    Info {
+        id: "finned-swordfish",
+        name: "Finned Swordfish",
+        difficulty: 4.0,
+        score: 200,
+        level: Difficulty::Hard,
+        make: || Box::new(FinnedFish::new(3)),
+    },
+    Info {
+        id: "franken-x-wing",
+        name: "Franken X-Wing",
+        difficulty: 4.0,
+        score: 300,
+        level: Difficulty::Expert,
+        make: || Box::new(FrankenFish::new(2)),
+    },
+    Info {
+        id: "two-string-kite",
+        name: "2-String Kite",
+        difficulty: 4.1,
+        score: 150,
+        level: Difficulty::Hard,
+        make: || Box::new(TwoStringKite),
+    },
+    Info {
+        id: "xy-wing",
+        name: "XY-Wing",
+        difficulty: 4.2,
+        score: 160,
+        level: Difficulty::Hard,
+        make: || Box::new(XYWing),
+    },
+    Info {
+        id: "turbot-fish",
+        name: "Turbot Fish",
+        difficulty: 4.2,
+        score: 120,
+        level: Difficulty::Hard,
+        make: || Box::new(TurbotFish),
+    },
+    Info {
+        id: "xyz-wing",
+        name: "XYZ-Wing",
+        difficulty: 4.4,
+        score: 180,
+        level: Difficulty::Hard,
+        make: || Box::new(XYZWing),
+    },
+    Info {
+        id: "w-wing",
+        name: "W-Wing",
+        difficulty: 4.4,
+        score: 150,
+        level: Difficulty::Hard,
+        make: || Box::new(WWing),
+    },
+    Info {
+        id: "unique-rectangle",
+        name: "Unique Rectangle",
+        difficulty: 4.5,
+        score: 100,
+        level: Difficulty::Hard,
+        make: || Box::new(UniqueRectangle),
+    },
+    Info {
+        id: "empty-rectangle",
+        name: "Empty Rectangle",
+        difficulty: 4.5,
+        score: 120,
+        level: Difficulty::Hard,
+        make: || Box::new(EmptyRectangle),
+    },
+    Info {
+        id: "simple-coloring",
+        name: "Simple Coloring",
+        difficulty: 4.5,
+        score: 150,
+        level: Difficulty::Hard,
+        make: || Box::new(SimpleColoring),
+    },
+    Info {
+        id: "franken-swordfish",
+        name: "Franken Swordfish",
+        difficulty: 4.6,
+        score: 350,
+        level: Difficulty::Expert,
+        make: || Box::new(FrankenFish::new(3)),
+    },
+    Info {
+        id: "mutant-x-wing",
+        name: "Mutant X-Wing",
+        difficulty: 4.6,
+        score: 450,
+        level: Difficulty::Expert,
+        make: || Box::new(MutantFish::new(2)),
+    },
+    Info {
+        id: "sue-de-coq",
+        name: "Sue de Coq",
+        difficulty: 5.0,
+        score: 250,
+        level: Difficulty::Expert,
+        make: || Box::new(SueDeCoq),
+    },
+    Info {
+        id: "naked-quad",
+        name: "Naked Quad",
+        difficulty: 5.0,
+        score: 120,
+        level: Difficulty::Hard,
+        make: || Box::new(NakedSubset::new(4)),
+    },
+    Info {
+        id: "jellyfish",
+        name: "Jellyfish",
+        difficulty: 5.2,
+        score: 160,
+        level: Difficulty::Hard,
+        make: || Box::new(Fish::new(4)),
+    },
+    Info {
+        id: "hidden-quad",
+        name: "Hidden Quad",
+        difficulty: 5.4,
+        score: 150,
+        level: Difficulty::Hard,
+        make: || Box::new(HiddenSubset::new(4)),
+    },
+    Info {
+        id: "finned-jellyfish",
+        name: "Finned Jellyfish",
+        difficulty: 5.4,
+        score: 240,
+        level: Difficulty::Expert,
+        make: || Box::new(FinnedFish::new(4)),
+    },
+    Info {
+        id: "multi-coloring",
+        name: "Multi-Coloring",
+        difficulty: 5.5,
+        score: 200,
+        level: Difficulty::Hard,
+        make: || Box::new(MultiColoring),
+    },
+    Info {
+        id: "bug-plus-one",
+        name: "BUG+1",
+        difficulty: 5.6,
+        score: 100,
+        level: Difficulty::Hard,
+        make: || Box::new(BugPlusOne),
+    },
+    Info {
+        id: "x-chain",
+        name: "X-Chain",
+        difficulty: 6.6,
+        score: 260,
+        level: Difficulty::Expert,
+        make: || Box::new(XChain::new()),
+    },
+    Info {
+        id: "xy-chain",
+        name: "XY-Chain",
+        difficulty: 6.6,
+        score: 260,
+        level: Difficulty::Expert,
+        make: || Box::new(XYChain::new()),
+    },
+    Info {
+        id: "aic",
+        name: "AIC",
+        difficulty: 7.0,
+        score: 280,
+        level: Difficulty::Expert,
+        make: || Box::new(Aic::new()),
+    },
+    Info {
+        id: "nice-loop",
+        name: "Nice Loop",
+        difficulty: 7.0,
+        score: 280,
+        level: Difficulty::Expert,
+        make: || Box::new(NiceLoop::new()),
+    },
+    Info {
+        id: "franken-jellyfish",
+        name: "Franken Jellyfish",
+        difficulty: 7.1,
+        score: 370,
+        level: Difficulty::Expert,
+        make: || Box::new(FrankenFish::new(4)),
+    },
+    Info {
+        id: "mutant-swordfish",
+        name: "Mutant Swordfish",
+        difficulty: 7.2,
+        score: 470,
+        level: Difficulty::Expert,
+        make: || Box::new(MutantFish::new(3)),
+    },
+    Info {
+        id: "als-xz",
+        name: "ALS-XZ",
+        difficulty: 7.5,
+        score: 300,
+        level: Difficulty::Expert,
+        make: || Box::new(AlsXz),
+    },
+    Info {
+        id: "nishio",
+        name: "Nishio",
+        difficulty: 7.5,
+        score: 500,
+        level: Difficulty::Expert,
+        make: || Box::new(Nishio::new()),
+    },
+    Info {
+        id: "mutant-jellyfish",
+        name: "Mutant Jellyfish",
+        difficulty: 7.6,
+        score: 470,
+        level: Difficulty::Expert,
+        make: || Box::new(MutantFish::new(4)),
+    },
+    Info {
+        id: "als-xy-wing",
+        name: "ALS-XY-Wing",
+        difficulty: 7.8,
+        score: 320,
+        level: Difficulty::Expert,
+        make: || Box::new(AlsXyWing),
+    },
+    Info {
+        id: "death-blossom",
+        name: "Death Blossom",
+        difficulty: 8.0,
+        score: 360,
+        level: Difficulty::Expert,
+        make: || Box::new(DeathBlossom),
+    },
+    Info {
+        id: "cell-forcing-chain",
+        name: "Cell Forcing Chain",
+        difficulty: 8.3,
+        score: 500,
+        level: Difficulty::Expert,
+        make: || Box::new(CellForcingChain::new()),
+    },
+    Info {
+        id: "unit-forcing-chain",
+        name: "Unit Forcing Chain",
+        difficulty: 8.5,
+        score: 500,
+        level: Difficulty::Expert,
+        make: || Box::new(UnitForcingChain::new()),
+    },
+];
+
+/// Returns the registry of built-in strategies, in priority order.
+pub fn registry() -> &'static [Info] {
+    REGISTRY
 }
 
-/// Returns all available strategies in priority order.
+/// Looks up a built-in strategy by its identifier.
+pub fn lookup(id: &str) -> Option<&'static Info> {
+    REGISTRY.iter().find(|info| info.id == id)
+}
+
+/// Looks up a built-in strategy by its display name.
+pub(crate) fn lookup_name(name: &str) -> Option<&'static Info> {
+    REGISTRY.iter().find(|info| info.name == name)
+}
+
+/// Returns the registry weight of the strategy called `name`, or 0.0 if it
+/// is not registered. No step of a registered strategy rates lower.
+pub(crate) fn base_difficulty(name: &str) -> f32 {
+    lookup_name(name).map_or(0.0, |info| info.difficulty)
+}
+
+/// Returns all available strategies in priority order, easiest first.
 pub fn all() -> Vec<Box<dyn Strategy>> {
-    vec![Box::new(NakedSingles), Box::new(HiddenSingles)]
+    REGISTRY.iter().map(Info::build).collect()
+}
+
+/// Builds the strategies with the given identifiers. They keep the registry's
+/// priority order whatever the order of `ids`.
+pub fn by_ids<S: AsRef<str>>(ids: &[S]) -> Result<Vec<Box<dyn Strategy>>, String> {
+    if let Some(id) = ids
+        .iter()
+        .map(AsRef::as_ref)
+        .find(|&id| lookup(id).is_none())
+    {
+        return Err(format!("Unknown strategy: {id}"));
+    }
+
+    Ok(REGISTRY
+        .iter()
+        .filter(|info| ids.iter().any(|id| id.as_ref() == info.id))
+        .map(Info::build)
+        .collect())
 }
 
 /// Fills cells that have only one candidate.
@@ -19,26 +467,19 @@ pub struct NakedSingles;
 
 impl Strategy for NakedSingles {
     fn name(&self) -> &'static str {
-        "Naked Singles"
+        "Naked Single"
     }
 
-    fn apply(&self, sudoku: &mut Sudoku) -> bool {
-        let mut progress = false;
-
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
         for r in 0..sudoku.size {
             for c in 0..sudoku.size {
-                if sudoku.grid[r][c].is_empty() {
-                    let cands = sudoku.candidates(r, c);
-                    if cands.len() == 1 {
-                        let val = *cands.iter().next().unwrap();
-                        let _ = sudoku.set(r, c, val);
-                        progress = true;
-                    }
+                if let Some(val) = sudoku.candidates(r, c).single_value() {
+                    return Some(Step::new(self.name()).place(r, c, val).cell(r, c));
                 }
             }
         }
 
-        progress
+        None
     }
 }
 
@@ -47,81 +488,2711 @@ pub struct HiddenSingles;
 
 impl Strategy for HiddenSingles {
     fn name(&self) -> &'static str {
-        "Hidden Singles"
+        "Hidden Single"
     }
 
-    fn apply(&self, sudoku: &mut Sudoku) -> bool {
-        let mut progress = false;
+    /// A hidden single in a box is easier to spot than one in a line.
+    fn difficulty(&self, step: &Step) -> f32 {
+        match step.units.first() {
+            Some(Unit::Box(_)) => 1.2,
+            _ => 1.5,
+        }
+    }
 
-        for i in 0..sudoku.size {
-            progress |= apply_row(sudoku, i);
-            progress |= apply_col(sudoku, i);
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        // Boxes first: that is where a human scans for hidden singles.
+        let n = sudoku.size;
+        let units = (0..n)
+            .map(Unit::Box)
+            .chain((0..n).map(Unit::Row))
+            .chain((0..n).map(Unit::Col));
+
+        for unit in units {
+            for val in 1..=n as u8 {
+                let mut cells = unit
+                    .cells(sudoku.box_size)
+                    .filter(|&(r, c)| sudoku.candidates(r, c).contains(val));
+
+                if let (Some((r, c)), None) = (cells.next(), cells.next()) {
+                    return Some(
+                        Step::new(self.name())
+                            .place(r, c, val)
+                            .unit(unit)
+                            .cell(r, c)
+                            .digit(val),
+                    );
+                }
+            }
         }
 
+        None
+    }
+}
+
+/// Finds `size` cells in a unit whose candidates together span exactly `size`
+/// digits, and removes those digits from the rest of the unit.
+pub struct NakedSubset {
+    size: usize,
+}
+
+impl NakedSubset {
+    /// Creates a naked subset strategy for pairs (2), triples (3) or quads (4).
+    pub fn new(size: usize) -> Self {
+        assert!((2..=4).contains(&size), "Subset size must be 2-4");
+        Self { size }
+    }
+}
+
+impl Strategy for NakedSubset {
+    fn name(&self) -> &'static str {
+        ["Naked Pair", "Naked Triple", "Naked Quad"][self.size - 2]
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
         let bs = sudoku.box_size;
-        for br in 0..bs {
-            for bc in 0..bs {
-                progress |= apply_box(sudoku, br, bc);
+
+        for unit in units(sudoku.size) {
+            let cells: Vec<_> = unit
+                .cells(bs)
+                .filter(|&(r, c)| (2..=self.size).contains(&sudoku.candidates(r, c).len()))
+                .collect();
+
+            let step = find_combination(cells.len(), self.size, |combo| {
+                let subset: Vec<_> = combo.iter().map(|&i| cells[i]).collect();
+                let digits = subset.iter().fold(Candidates::EMPTY, |acc, &(r, c)| {
+                    acc | sudoku.candidates(r, c)
+                });
+                if digits.len() != self.size {
+                    return None;
+                }
+
+                // Every unit shared by the whole subset is locked, not just this one.
+                let shared: Vec<_> = shared_units(&subset, bs).collect();
+                let mut step = Step::new(self.name());
+                for &cover in &shared {
+                    for (r, c) in cover.cells(bs) {
+                        if subset.contains(&(r, c)) {
+                            continue;
+                        }
+                        for val in sudoku.candidates(r, c) & digits {
+                            if !step.eliminations.contains(&(r, c, val)) {
+                                step.eliminations.push((r, c, val));
+                            }
+                        }
+                    }
+                }
+                if step.eliminations.is_empty() {
+                    return None;
+                }
+
+                step.units = shared;
+                step.cells = subset;
+                step.digits = digits;
+                Some(step)
+            });
+            if step.is_some() {
+                return step;
             }
         }
 
-        progress
+        None
+    }
+}
+
+/// Finds `size` digits confined to the same `size` cells of a unit, and removes
+/// all other candidates from those cells.
+pub struct HiddenSubset {
+    size: usize,
+}
+
+impl HiddenSubset {
+    /// Creates a hidden subset strategy for pairs (2), triples (3) or quads (4).
+    pub fn new(size: usize) -> Self {
+        assert!((2..=4).contains(&size), "Subset size must be 2-4");
+        Self { size }
     }
 }
 
-fn apply_row(sudoku: &mut Sudoku, row: usize) -> bool {
-    let mut progress = false;
+impl Strategy for HiddenSubset {
+    fn name(&self) -> &'static str {
+        ["Hidden Pair", "Hidden Triple", "Hidden Quad"][self.size - 2]
+    }
 
-    for val in 1..=sudoku.size as u8 {
-        let cols: Vec<_> = (0..sudoku.size)
-            .filter(|&c| sudoku.grid[row][c].is_empty() && sudoku.candidates(row, c).contains(&val))
-            .collect();
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let n = sudoku.size;
+
+        for unit in units(n) {
+            let positions: Vec<_> = (1..=n as u8)
+                .map(|val| (val, cells_with(sudoku, unit, val)))
+                .filter(|(_, cells)| (1..=self.size).contains(&cells.len()))
+                .collect();
 
-        if cols.len() == 1 {
-            let _ = sudoku.set(row, cols[0], val);
-            progress = true;
+            let step = find_combination(positions.len(), self.size, |combo| {
+                let mut subset: Vec<(usize, usize)> = Vec::new();
+                let mut digits = Candidates::EMPTY;
+                for &i in combo {
+                    let (val, ref cells) = positions[i];
+                    digits.insert(val);
+                    for &cell in cells {
+                        if !subset.contains(&cell) {
+                            subset.push(cell);
+                        }
+                    }
+                }
+                if subset.len() != self.size {
+                    return None;
+                }
+
+                let mut step = Step::new(self.name()).unit(unit);
+                for &(r, c) in &subset {
+                    for val in sudoku.candidates(r, c) - digits {
+                        step.eliminations.push((r, c, val));
+                    }
+                }
+                if step.eliminations.is_empty() {
+                    return None;
+                }
+
+                subset.sort_unstable();
+                step.cells = subset;
+                step.digits = digits;
+                Some(step)
+            });
+            if step.is_some() {
+                return step;
+            }
         }
+
+        None
     }
+}
 
-    progress
+/// Basic fish: a digit confined to the same `size` columns within `size` rows
+/// (or vice versa) can be removed from the rest of those columns.
+pub struct Fish {
+    size: usize,
 }
 
-fn apply_col(sudoku: &mut Sudoku, col: usize) -> bool {
-    let mut progress = false;
+impl Fish {
+    /// Creates a fish strategy: X-Wing (2), Swordfish (3) or Jellyfish (4).
+    pub fn new(size: usize) -> Self {
+        assert!((2..=4).contains(&size), "Fish size must be 2-4");
+        Self { size }
+    }
+}
 
-    for val in 1..=sudoku.size as u8 {
-        let rows: Vec<_> = (0..sudoku.size)
-            .filter(|&r| sudoku.grid[r][col].is_empty() && sudoku.candidates(r, col).contains(&val))
-            .collect();
+impl Strategy for Fish {
+    fn name(&self) -> &'static str {
+        ["X-Wing", "Swordfish", "Jellyfish"][self.size - 2]
+    }
 
-        if rows.len() == 1 {
-            let _ = sudoku.set(rows[0], col, val);
-            progress = true;
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let n = sudoku.size;
+        let orientations: [(UnitKind, UnitKind); 2] =
+            [(Unit::Row, Unit::Col), (Unit::Col, Unit::Row)];
+
+        for val in 1..=n as u8 {
+            for (base_unit, cover_unit) in orientations {
+                let lines: Vec<_> = (0..n)
+                    .map(|i| (i, line_mask(sudoku, base_unit(i), val)))
+                    .filter(|&(_, mask)| (2..=self.size).contains(&(mask.count_ones() as usize)))
+                    .collect();
+
+                let step = find_combination(lines.len(), self.size, |combo| {
+                    let cover = combo.iter().fold(0, |acc, &i| acc | lines[i].1);
+                    if cover.count_ones() as usize != self.size {
+                        return None;
+                    }
+
+                    let base: Vec<_> = combo.iter().map(|&i| base_unit(lines[i].0)).collect();
+                    let covers: Vec<_> = bit_indices(cover).map(cover_unit).collect();
+                    let mut step = Step::new(self.name()).digit(val);
+
+                    for &unit in &covers {
+                        for (r, c) in cells_with(sudoku, unit, val) {
+                            if !base.iter().any(|b| b.contains(r, c, sudoku.box_size)) {
+                                step.eliminations.push((r, c, val));
+                            }
+                        }
+                    }
+                    if step.eliminations.is_empty() {
+                        return None;
+                    }
+
+                    step.cells = base
+                        .iter()
+                        .flat_map(|&unit| cells_with(sudoku, unit, val))
+                        .collect();
+                    step.units = base.into_iter().chain(covers).collect();
+                    Some(step)
+                });
+                if step.is_some() {
+                    return step;
+                }
+            }
         }
+
+        None
     }
+}
 
-    progress
+/// Finned and sashimi fish: a fish whose base lines have extra candidates (fins)
+/// in a single box. Either the fish holds or a fin is true, so only cover cells
+/// in the fin box can be eliminated.
+pub struct FinnedFish {
+    size: usize,
 }
 
-fn apply_box(sudoku: &mut Sudoku, br: usize, bc: usize) -> bool {
-    let mut progress = false;
-    let bs = sudoku.box_size;
-    let (sr, sc) = (br * bs, bc * bs);
+impl FinnedFish {
+    /// Creates a finned fish strategy: X-Wing (2), Swordfish (3) or Jellyfish (4).
+    pub fn new(size: usize) -> Self {
+        assert!((2..=4).contains(&size), "Fish size must be 2-4");
+        Self { size }
+    }
+
+    fn sashimi_name(&self) -> &'static str {
+        ["Sashimi X-Wing", "Sashimi Swordfish", "Sashimi Jellyfish"][self.size - 2]
+    }
+}
+
+impl Strategy for FinnedFish {
+    fn name(&self) -> &'static str {
+        ["Finned X-Wing", "Finned Swordfish", "Finned Jellyfish"][self.size - 2]
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let (n, bs) = (sudoku.size, sudoku.box_size);
+        let orientations: [(UnitKind, UnitKind); 2] =
+            [(Unit::Row, Unit::Col), (Unit::Col, Unit::Row)];
+
+        for val in 1..=n as u8 {
+            for (base_unit, cover_unit) in orientations {
+                // Fins sit in one box, so a base line holds at most `bs` of them.
+                let lines: Vec<_> = (0..n)
+                    .map(|i| (i, line_mask(sudoku, base_unit(i), val)))
+                    .filter(|&(_, mask)| {
+                        (1..=self.size + bs).contains(&(mask.count_ones() as usize))
+                    })
+                    .collect();
+
+                let step = find_combination(lines.len(), self.size, |combo| {
+                    let base: Vec<_> = combo.iter().map(|&i| lines[i]).collect();
+                    let union = base.iter().fold(0, |acc, &(_, mask)| acc | mask);
+                    if union.count_ones() as usize <= self.size {
+                        return None;
+                    }
+
+                    // Fins share a box, so every index outside one box band must be covered.
+                    (0..bs).find_map(|band| {
+                        let band_mask = ((1 << bs) - 1) << (band * bs);
+                        let required = union & !band_mask;
+                        let need = self.size.checked_sub(required.count_ones() as usize)?;
+                        let extra: Vec<_> = bit_indices(union & band_mask).collect();
+
+                        let check = |cover| {
+                            self.finned_step(sudoku, val, &base, cover, base_unit, cover_unit)
+                        };
+                        if need == 0 {
+                            return check(required);
+                        }
+                        find_combination(extra.len(), need, |picked| {
+                            check(picked.iter().fold(required, |acc, &i| acc | 1 << extra[i]))
+                        })
+                    })
+                });
+                if step.is_some() {
+                    return step;
+                }
+            }
+        }
+
+        None
+    }
+}
 
-    for val in 1..=sudoku.size as u8 {
-        let cells: Vec<_> = (sr..sr + bs)
-            .flat_map(|r| (sc..sc + bs).map(move |c| (r, c)))
-            .filter(|&(r, c)| {
-                sudoku.grid[r][c].is_empty() && sudoku.candidates(r, c).contains(&val)
+impl FinnedFish {
+    /// Checks one choice of base lines and cover mask for a finned fish.
+    fn finned_step(
+        &self,
+        sudoku: &Sudoku,
+        val: u8,
+        base: &[(usize, u64)],
+        cover: u64,
+        base_unit: UnitKind,
+        cover_unit: UnitKind,
+    ) -> Option<Step> {
+        let bs = sudoku.box_size;
+
+        // Every base line must still touch the cover sets.
+        if base.iter().any(|&(_, mask)| mask & cover == 0) {
+            return None;
+        }
+
+        let fins: Vec<_> = base
+            .iter()
+            .flat_map(|&(i, mask)| {
+                bit_indices(mask & !cover).map(move |j| base_unit(i).cell(j, bs))
             })
             .collect();
+        let &(fr, fc) = fins.first()?;
+        let fin_box = sudoku.box_index(fr, fc);
+        if fins.iter().any(|&(r, c)| sudoku.box_index(r, c) != fin_box) {
+            return None;
+        }
+
+        let base_units: Vec<_> = base.iter().map(|&(i, _)| base_unit(i)).collect();
+        let cover_units: Vec<_> = bit_indices(cover).map(cover_unit).collect();
+        let name = if base
+            .iter()
+            .any(|&(_, mask)| (mask & cover).count_ones() == 1)
+        {
+            self.sashimi_name()
+        } else {
+            self.name()
+        };
+
+        let mut step = Step::new(name).digit(val);
+        for &unit in &cover_units {
+            for (r, c) in cells_with(sudoku, unit, val) {
+                if sudoku.box_index(r, c) == fin_box
+                    && !base_units.iter().any(|b| b.contains(r, c, bs))
+                {
+                    step.eliminations.push((r, c, val));
+                }
+            }
+        }
+        if step.eliminations.is_empty() {
+            return None;
+        }
+
+        step.cells = base
+            .iter()
+            .flat_map(|&(i, mask)| bit_indices(mask & cover).map(move |j| base_unit(i).cell(j, bs)))
+            .collect();
+        step.units = base_units.into_iter().chain(cover_units).collect();
+        step.detail = Detail::Fins(fins);
+        Some(step)
+    }
+}
+
+/// A bivalue pivot {x,y} sees pincers {x,z} and {y,z}; whichever value the pivot
+/// takes, one pincer is z, so z goes from every cell seeing both pincers.
+pub struct XYWing;
 
-        if cells.len() == 1 {
-            let (r, c) = cells[0];
-            let _ = sudoku.set(r, c, val);
-            progress = true;
+impl Strategy for XYWing {
+    fn name(&self) -> &'static str {
+        "XY-Wing"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let bs = sudoku.box_size;
+        let bivalue = cells_by_count(sudoku, 2);
+
+        for &pivot in &bivalue {
+            let pc = candidates_at(sudoku, pivot);
+            let wings: Vec<_> = bivalue
+                .iter()
+                .copied()
+                .filter(|&w| sees(pivot, w, bs))
+                .filter(|&w| (candidates_at(sudoku, w) & pc).len() == 1)
+                .collect();
+
+            for (i, &a) in wings.iter().enumerate() {
+                for &b in &wings[i + 1..] {
+                    let (ac, bc) = (candidates_at(sudoku, a), candidates_at(sudoku, b));
+                    let Some(z) = (ac & bc).single_value() else {
+                        continue;
+                    };
+                    if pc.contains(z) || (ac | bc) - Candidates::single(z) != pc {
+                        continue;
+                    }
+
+                    if let Some(step) =
+                        wing_step(self.name(), sudoku, z, &[pivot], &[a, b], &[a, b])
+                    {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// A pivot {x,y,z} sees pincers {x,z} and {y,z}; z goes from every cell seeing
+/// all three.
+pub struct XYZWing;
+
+impl Strategy for XYZWing {
+    fn name(&self) -> &'static str {
+        "XYZ-Wing"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let bs = sudoku.box_size;
+        let bivalue = cells_by_count(sudoku, 2);
+
+        for pivot in cells_by_count(sudoku, 3) {
+            let pc = candidates_at(sudoku, pivot);
+            let wings: Vec<_> = bivalue
+                .iter()
+                .copied()
+                .filter(|&w| {
+                    sees(pivot, w, bs) && candidates_at(sudoku, w) - pc == Candidates::EMPTY
+                })
+                .collect();
+
+            for (i, &a) in wings.iter().enumerate() {
+                for &b in &wings[i + 1..] {
+                    let (ac, bc) = (candidates_at(sudoku, a), candidates_at(sudoku, b));
+                    let Some(z) = (ac & bc).single_value() else {
+                        continue;
+                    };
+                    if ac | bc != pc {
+                        continue;
+                    }
+
+                    if let Some(step) =
+                        wing_step(self.name(), sudoku, z, &[pivot], &[a, b], &[pivot, a, b])
+                    {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Two bivalue cells {x,y} joined by a strong link on x: one of them must be y,
+/// so y goes from every cell seeing both.
+pub struct WWing;
+
+impl Strategy for WWing {
+    fn name(&self) -> &'static str {
+        "W-Wing"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let bs = sudoku.box_size;
+        let bivalue = cells_by_count(sudoku, 2);
+
+        for (i, &a) in bivalue.iter().enumerate() {
+            let pair = candidates_at(sudoku, a);
+            for &b in &bivalue[i + 1..] {
+                if candidates_at(sudoku, b) != pair || sees(a, b, bs) {
+                    continue;
+                }
+
+                for x in pair {
+                    let y = (pair - Candidates::single(x)).first()?;
+                    for unit in units(sudoku.size) {
+                        let link = cells_with(sudoku, unit, x);
+                        let &[p, q] = link.as_slice() else {
+                            continue;
+                        };
+                        if [p, q].iter().any(|&cell| cell == a || cell == b) {
+                            continue;
+                        }
+
+                        let (p, q) = if sees(p, a, bs) && sees(q, b, bs) {
+                            (p, q)
+                        } else if sees(q, a, bs) && sees(p, b, bs) {
+                            (q, p)
+                        } else {
+                            continue;
+                        };
+
+                        if let Some(mut step) =
+                            wing_step(self.name(), sudoku, y, &[p, q], &[a, b], &[a, b])
+                        {
+                            step.digits.insert(x);
+                            step.units.push(unit);
+                            return Some(step);
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Two parallel strong links whose ends share one column (or row): one of the
+/// other two ends must hold the digit.
+pub struct Skyscraper;
+
+impl Strategy for Skyscraper {
+    fn name(&self) -> &'static str {
+        "Skyscraper"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        for val in 1..=sudoku.size as u8 {
+            let links = strong_links(sudoku, val);
+            for (i, &(u1, a1, b1)) in links.iter().enumerate() {
+                for &(u2, a2, b2) in &links[i + 1..] {
+                    let along = match (u1, u2) {
+                        (Unit::Row(_), Unit::Row(_)) => |(_, c): (usize, usize)| c,
+                        (Unit::Col(_), Unit::Col(_)) => |(r, _): (usize, usize)| r,
+                        _ => continue,
+                    };
+
+                    for (base1, roof1) in [(a1, b1), (b1, a1)] {
+                        for (base2, roof2) in [(a2, b2), (b2, a2)] {
+                            if along(base1) != along(base2) || along(roof1) == along(roof2) {
+                                continue;
+                            }
+                            let chain = [roof1, base1, base2, roof2];
+                            if let Some(step) =
+                                link_step(self.name(), sudoku, val, &chain, &[u1, u2])
+                            {
+                                return Some(step);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// A row strong link and a column strong link with ends in the same box: one
+/// of their far ends must hold the digit.
+pub struct TwoStringKite;
+
+impl Strategy for TwoStringKite {
+    fn name(&self) -> &'static str {
+        "2-String Kite"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let bs = sudoku.box_size;
+
+        for val in 1..=sudoku.size as u8 {
+            let links = strong_links(sudoku, val);
+            let rows = links.iter().filter(|l| matches!(l.0, Unit::Row(_)));
+            for &(u1, a1, b1) in rows {
+                let cols = links.iter().filter(|l| matches!(l.0, Unit::Col(_)));
+                for &(u2, a2, b2) in cols {
+                    for (near1, far1) in [(a1, b1), (b1, a1)] {
+                        for (near2, far2) in [(a2, b2), (b2, a2)] {
+                            let distinct =
+                                near1 != near2 && far1 != far2 && far1 != near2 && far2 != near1;
+                            if !distinct || !same_box(near1, near2, bs) {
+                                continue;
+                            }
+                            let chain = [far1, near1, near2, far2];
+                            if let Some(step) =
+                                link_step(self.name(), sudoku, val, &chain, &[u1, u2])
+                            {
+                                return Some(step);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Two strong links joined by a weak link, in any units: one of the two outer
+/// ends must hold the digit. Covers the patterns not caught as a Skyscraper or
+/// 2-String Kite.
+pub struct TurbotFish;
+
+impl Strategy for TurbotFish {
+    fn name(&self) -> &'static str {
+        "Turbot Fish"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let bs = sudoku.box_size;
+
+        for val in 1..=sudoku.size as u8 {
+            let links = strong_links(sudoku, val);
+            for (i, &(u1, a1, b1)) in links.iter().enumerate() {
+                for &(u2, a2, b2) in &links[i + 1..] {
+                    for (start, mid1) in [(a1, b1), (b1, a1)] {
+                        for (mid2, end) in [(a2, b2), (b2, a2)] {
+                            let cells = [start, mid1, mid2, end];
+                            let distinct = (0..4).all(|i| (i + 1..4).all(|j| cells[i] != cells[j]));
+                            if !distinct || !sees(mid1, mid2, bs) {
+                                continue;
+                            }
+                            if let Some(step) =
+                                link_step(self.name(), sudoku, val, &cells, &[u1, u2])
+                            {
+                                return Some(step);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// A box whose candidates for a digit fit in one row and one column, combined
+/// with a strong link that sees that row (or column) from outside the box.
+pub struct EmptyRectangle;
+
+impl Strategy for EmptyRectangle {
+    fn name(&self) -> &'static str {
+        "Empty Rectangle"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let (n, bs) = (sudoku.size, sudoku.box_size);
+
+        for val in 1..=n as u8 {
+            let links = strong_links(sudoku, val);
+
+            for b in 0..n {
+                let cells = cells_with(sudoku, Unit::Box(b), val);
+                if cells.len() < 2 {
+                    continue;
+                }
+                let (br, bc) = (b / bs * bs, b % bs * bs);
+
+                for er_row in br..br + bs {
+                    for er_col in bc..bc + bs {
+                        if !cells.iter().all(|&(r, c)| r == er_row || c == er_col) {
+                            continue;
+                        }
+
+                        for &(unit, p, q) in &links {
+                            // A column link with one end on the ER row removes the digit where
+                            // its other end's row meets the ER column; likewise for row links.
+                            let target = match unit {
+                                Unit::Col(c) if !(bc..bc + bs).contains(&c) => {
+                                    let (near, far) = if p.0 == er_row { (p, q) } else { (q, p) };
+                                    (near.0 == er_row && !(br..br + bs).contains(&far.0))
+                                        .then_some(((far.0, er_col), near, far))
+                                }
+                                Unit::Row(r) if !(br..br + bs).contains(&r) => {
+                                    let (near, far) = if p.1 == er_col { (p, q) } else { (q, p) };
+                                    (near.1 == er_col && !(bc..bc + bs).contains(&far.1))
+                                        .then_some(((er_row, far.1), near, far))
+                                }
+                                _ => None,
+                            };
+                            let Some(((tr, tc), near, far)) = target else {
+                                continue;
+                            };
+                            if !sudoku.candidates(tr, tc).contains(val) {
+                                continue;
+                            }
+
+                            let mut step = Step::new(self.name())
+                                .eliminate(tr, tc, val)
+                                .unit(Unit::Box(b))
+                                .unit(unit)
+                                .digit(val);
+                            step.cells = cells.iter().copied().chain([near, far]).collect();
+                            return Some(step);
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Colors the cells of each conjugate-pair cluster of a digit alternately.
+/// A color appearing twice in one unit is false (wrap); a cell seeing both
+/// colors cannot hold the digit (trap).
+pub struct SimpleColoring;
+
+impl Strategy for SimpleColoring {
+    fn name(&self) -> &'static str {
+        "Simple Coloring"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let bs = sudoku.box_size;
+
+        for val in 1..=sudoku.size as u8 {
+            for colors in clusters(sudoku, val) {
+                let [a, b] = &colors;
+
+                // Wrap: two cells of one color see each other, so that color is false.
+                for (false_color, _) in [(a, b), (b, a)] {
+                    if any_sees(false_color, false_color, bs) {
+                        let mut step = Step::new(self.name()).digit(val);
+                        step.eliminations = false_color.iter().map(|&(r, c)| (r, c, val)).collect();
+                        return Some(coloring_step(step, vec![colors.clone()]));
+                    }
+                }
+
+                // Trap: one color is true, so cells seeing both colors lose the digit.
+                let mut step = Step::new(self.name()).digit(val);
+                step.eliminations = cells_seeing_both(sudoku, val, a, b, bs);
+                if !step.eliminations.is_empty() {
+                    return Some(coloring_step(step, vec![colors.clone()]));
+                }
+            }
         }
+
+        None
+    }
+}
+
+/// Combines two conjugate-pair clusters of a digit. If a color of one sees a
+/// color of the other, one of their opposites is true; if a color sees both
+/// colors of another cluster, it is false.
+pub struct MultiColoring;
+
+impl Strategy for MultiColoring {
+    fn name(&self) -> &'static str {
+        "Multi-Coloring"
     }
 
-    progress
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let bs = sudoku.box_size;
+
+        for val in 1..=sudoku.size as u8 {
+            let clusters = clusters(sudoku, val);
+
+            for (i, first) in clusters.iter().enumerate() {
+                for (j, second) in clusters.iter().enumerate() {
+                    if i == j {
+                        continue;
+                    }
+
+                    for x in 0..2 {
+                        let (a, a_opp) = (&first[x], &first[1 - x]);
+
+                        // A color seeing both colors of the other cluster is false.
+                        if any_sees(a, &second[0], bs) && any_sees(a, &second[1], bs) {
+                            let mut step = Step::new(self.name()).digit(val);
+                            step.eliminations = a.iter().map(|&(r, c)| (r, c, val)).collect();
+                            return Some(coloring_step(step, vec![first.clone(), second.clone()]));
+                        }
+
+                        // Each ordered pair of clusters is visited twice; handle the
+                        // symmetric case once.
+                        if i > j {
+                            continue;
+                        }
+                        for y in 0..2 {
+                            let (b, b_opp) = (&second[y], &second[1 - y]);
+                            if !any_sees(a, b, bs) {
+                                continue;
+                            }
+
+                            let mut step = Step::new(self.name()).digit(val);
+                            step.eliminations = cells_seeing_both(sudoku, val, a_opp, b_opp, bs);
+                            if !step.eliminations.is_empty() {
+                                return Some(coloring_step(
+                                    step,
+                                    vec![first.clone(), second.clone()],
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Most fins a Franken or Mutant fish may have.
+const MAX_FINS: usize = 3;
+
+/// Franken fish: a fish whose base and cover sets mix boxes with rows or
+/// columns, e.g. rows and boxes as base with columns and boxes as cover. Fins
+/// are allowed anywhere; eliminations must then see them all.
+pub struct FrankenFish {
+    size: usize,
+}
+
+impl FrankenFish {
+    /// Creates a Franken fish strategy: X-Wing (2), Swordfish (3) or Jellyfish (4).
+    pub fn new(size: usize) -> Self {
+        assert!((2..=4).contains(&size), "Fish size must be 2-4");
+        Self { size }
+    }
+}
+
+impl Strategy for FrankenFish {
+    fn name(&self) -> &'static str {
+        ["Franken X-Wing", "Franken Swordfish", "Franken Jellyfish"][self.size - 2]
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let names = [
+            self.name(),
+            [
+                "Finned Franken X-Wing",
+                "Finned Franken Swordfish",
+                "Finned Franken Jellyfish",
+            ][self.size - 2],
+        ];
+        FishSearch::find(sudoku, self.size, FishShape::Franken, names)
+    }
+}
+
+/// Mutant fish: a fish whose base and cover sets may be any units at all, as
+/// long as no two base units share a candidate. Fins are allowed anywhere;
+/// eliminations must then see them all.
+pub struct MutantFish {
+    size: usize,
+}
+
+impl MutantFish {
+    /// Creates a Mutant fish strategy: X-Wing (2), Swordfish (3) or Jellyfish (4).
+    pub fn new(size: usize) -> Self {
+        assert!((2..=4).contains(&size), "Fish size must be 2-4");
+        Self { size }
+    }
+}
+
+impl Strategy for MutantFish {
+    fn name(&self) -> &'static str {
+        ["Mutant X-Wing", "Mutant Swordfish", "Mutant Jellyfish"][self.size - 2]
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let names = [
+            self.name(),
+            [
+                "Finned Mutant X-Wing",
+                "Finned Mutant Swordfish",
+                "Finned Mutant Jellyfish",
+            ][self.size - 2],
+        ];
+        FishSearch::find(sudoku, self.size, FishShape::Mutant, names)
+    }
+}
+
+/// The kinds of units a generalized fish uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FishShape {
+    /// Rows and boxes against columns and boxes, or the other way round.
+    Franken,
+    /// Anything else mixing rows and columns.
+    Mutant,
+}
+
+impl FishShape {
+    /// Classifies a fish; basic fish (rows against columns) have no shape.
+    fn of(base: &[Unit], cover: &[Unit]) -> Option<Self> {
+        let all = |units: &[Unit], f: fn(&Unit) -> bool| units.iter().all(f);
+        let not_row = |u: &Unit| !matches!(u, Unit::Row(_));
+        let not_col = |u: &Unit| !matches!(u, Unit::Col(_));
+
+        let boxes = base.iter().chain(cover).any(|u| matches!(u, Unit::Box(_)));
+        let franken = (all(base, not_col) && all(cover, not_row))
+            || (all(base, not_row) && all(cover, not_col));
+        match (franken, boxes) {
+            (true, false) => None,
+            (true, true) => Some(Self::Franken),
+            (false, _) => Some(Self::Mutant),
+        }
+    }
+}
+
+/// Search for fish of one digit with arbitrary base and cover units.
+///
+/// Cells holding the digit are numbered, and sets of them are bitmasks over
+/// those numbers. Digits with more than 128 candidates are skipped; such
+/// fish only matter late in a solve.
+struct FishSearch<'a> {
+    sudoku: &'a Sudoku,
+    val: u8,
+    size: usize,
+    shape: FishShape,
+    /// Plain and finned step names.
+    names: [&'static str; 2],
+    /// Cells holding the digit.
+    cells: Vec<(usize, usize)>,
+    /// For each cell, the cells it sees.
+    peers: Vec<u128>,
+    /// Units holding the digit, with their cells that do.
+    units: Vec<(Unit, u128)>,
+}
+
+impl<'a> FishSearch<'a> {
+    /// Finds the first fish of the given size and shape over all digits.
+    fn find(
+        sudoku: &'a Sudoku,
+        size: usize,
+        shape: FishShape,
+        names: [&'static str; 2],
+    ) -> Option<Step> {
+        let (n, bs) = (sudoku.size, sudoku.box_size);
+
+        (1..=n as u8).find_map(|val| {
+            let cells: Vec<_> = (0..n)
+                .flat_map(|r| (0..n).map(move |c| (r, c)))
+                .filter(|&(r, c)| sudoku.candidates(r, c).contains(val))
+                .collect();
+            if cells.len() > 128 {
+                return None;
+            }
+
+            let mask = |f: &dyn Fn((usize, usize)) -> bool| {
+                (0..cells.len())
+                    .filter(|&i| f(cells[i]))
+                    .fold(0u128, |acc, i| acc | 1 << i)
+            };
+            let peers = cells.iter().map(|&a| mask(&|b| sees(a, b, bs))).collect();
+            let units = units(n)
+                .map(|unit| (unit, mask(&|(r, c)| unit.contains(r, c, bs))))
+                .filter(|&(_, m)| m != 0)
+                .collect();
+
+            let search = Self {
+                sudoku,
+                val,
+                size,
+                shape,
+                names,
+                cells,
+                peers,
+                units,
+            };
+            search.bases(0, &mut Vec::new(), 0)
+        })
+    }
+
+    /// Returns the mask of a unit's cells holding the digit.
+    fn unit_mask(&self, unit: Unit) -> u128 {
+        self.units
+            .iter()
+            .find(|&&(u, _)| u == unit)
+            .map_or(0, |&(_, m)| m)
+    }
+
+    /// Picks base units from `from` on, no two sharing a candidate cell.
+    fn bases(&self, from: usize, base: &mut Vec<Unit>, cells: u128) -> Option<Step> {
+        if base.len() == self.size {
+            return self.covers(base, cells, &mut Vec::new(), 0, 0, !0);
+        }
+
+        for (i, &(unit, mask)) in self.units.iter().enumerate().skip(from) {
+            if mask & cells != 0 {
+                continue;
+            }
+            // A Franken base never mixes rows with columns.
+            if self.shape == FishShape::Franken && base.iter().any(|&b| crosses(b, unit)) {
+                continue;
+            }
+
+            base.push(unit);
+            let step = self.bases(i + 1, base, cells | mask);
+            base.pop();
+            if step.is_some() {
+                return step;
+            }
+        }
+
+        None
+    }
+
+    /// Covers the base cells one at a time, each by a unit through it or by
+    /// calling it a fin. `targets` holds the cells seeing every fin so far.
+    fn covers(
+        &self,
+        base: &[Unit],
+        cells: u128,
+        cover: &mut Vec<Unit>,
+        covered: u128,
+        fins: u128,
+        targets: u128,
+    ) -> Option<Step> {
+        let open = cells & !covered & !fins;
+        if open == 0 {
+            return (cover.len() == self.size)
+                .then(|| self.step(base, cells, cover, covered, fins, targets))
+                .flatten();
+        }
+
+        let i = open.trailing_zeros() as usize;
+        let (r, c) = self.cells[i];
+
+        if cover.len() < self.size {
+            let through = [
+                Unit::Row(r),
+                Unit::Col(c),
+                Unit::Box(self.sudoku.box_index(r, c)),
+            ];
+            for unit in through {
+                // A Franken cover never mixes rows with columns, nor shares a
+                // line direction with the base.
+                if base.contains(&unit)
+                    || (self.shape == FishShape::Franken
+                        && (cover.iter().any(|&u| crosses(u, unit))
+                            || base.iter().any(|&u| parallel(u, unit))))
+                {
+                    continue;
+                }
+                cover.push(unit);
+                let covered = covered | self.unit_mask(unit);
+                let step = self.covers(base, cells, cover, covered, fins, targets);
+                cover.pop();
+                if step.is_some() {
+                    return step;
+                }
+            }
+        }
+
+        let targets = targets & self.peers[i] & !cells;
+        if fins.count_ones() < MAX_FINS as u32 && targets != 0 {
+            return self.covers(base, cells, cover, covered, fins | 1 << i, targets);
+        }
+
+        None
+    }
+
+    /// Builds the step for a complete fish, if it has the right shape and
+    /// eliminates something.
+    fn step(
+        &self,
+        base: &[Unit],
+        cells: u128,
+        cover: &[Unit],
+        covered: u128,
+        fins: u128,
+        targets: u128,
+    ) -> Option<Step> {
+        // A fin a later cover took in is found again without it.
+        if fins & covered != 0 || FishShape::of(base, cover) != Some(self.shape) {
+            return None;
+        }
+        let elims = covered & !cells & targets;
+        if elims == 0 {
+            return None;
+        }
+
+        let indices = |mask: u128| {
+            (0..self.cells.len())
+                .filter(move |&i| mask & 1 << i != 0)
+                .map(|i| self.cells[i])
+        };
+        let name = self.names[usize::from(fins != 0)];
+        let mut step = Step::new(name).digit(self.val);
+        step.eliminations = indices(elims).map(|(r, c)| (r, c, self.val)).collect();
+        step.cells = indices(cells & !fins).collect();
+        step.units = base.iter().chain(cover).copied().collect();
+        if fins != 0 {
+            step.detail = Detail::Fins(indices(fins).collect());
+        }
+        Some(step)
+    }
+}
+
+/// Returns true if both units are rows or both are columns.
+fn parallel(a: Unit, b: Unit) -> bool {
+    matches!(
+        (a, b),
+        (Unit::Row(_), Unit::Row(_)) | (Unit::Col(_), Unit::Col(_))
+    )
+}
+
+/// Returns true if one unit is a row and the other a column.
+fn crosses(a: Unit, b: Unit) -> bool {
+    matches!(
+        (a, b),
+        (Unit::Row(_), Unit::Col(_)) | (Unit::Col(_), Unit::Row(_))
+    )
+}
+
+/// Implements `new`, `Default` and a builder setter for a strategy whose only
+/// field is a limit on how far it searches.
+macro_rules! search_limit {
+    (
+        $(#[$new_doc:meta])*
+        $ty:ident::new,
+        $(#[$set_doc:meta])*
+        $field:ident = $default:expr $(,)?
+    ) => {
+        impl $ty {
+            $(#[$new_doc])*
+            pub fn new() -> Self {
+                Self { $field: $default }
+            }
+
+            $(#[$set_doc])*
+            pub fn $field(mut self, n: usize) -> Self {
+                self.$field = n;
+                self
+            }
+        }
+
+        impl Default for $ty {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+}
+
+/// Default maximum number of links in a chain.
+const MAX_CHAIN_LENGTH: usize = 20;
+
+/// Alternating strong and weak links on a single digit: one end of the chain
+/// holds the digit, so every cell seeing both ends loses it.
+pub struct XChain {
+    max_length: usize,
+}
+
+search_limit! {
+    /// Creates an X-Chain search over chains of up to 20 links.
+    XChain::new,
+    /// Sets the maximum number of links in a chain.
+    max_length = MAX_CHAIN_LENGTH,
+}
+
+impl Strategy for XChain {
+    fn name(&self) -> &'static str {
+        "X-Chain"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let rules = Rules {
+            bivalue_only: false,
+            conjugate: true,
+            bivalue: false,
+            cell_weak: false,
+            groups: false,
+        };
+        let found = Graph::new(sudoku, rules).shortest_elimination(sudoku, self.max_length)?;
+        Some(chain_step(self.name(), found))
+    }
+}
+
+/// A chain through bivalue cells, each linked to the next by a shared digit:
+/// one end of the chain holds the digit the two ends have in common.
+pub struct XYChain {
+    max_length: usize,
+}
+
+search_limit! {
+    /// Creates an XY-Chain search over chains of up to 20 links.
+    XYChain::new,
+    /// Sets the maximum number of links in a chain.
+    max_length = MAX_CHAIN_LENGTH,
+}
+
+impl Strategy for XYChain {
+    fn name(&self) -> &'static str {
+        "XY-Chain"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let rules = Rules {
+            bivalue_only: true,
+            conjugate: false,
+            bivalue: true,
+            cell_weak: false,
+            groups: false,
+        };
+        let found = Graph::new(sudoku, rules).shortest_elimination(sudoku, self.max_length)?;
+        Some(chain_step(self.name(), found))
+    }
+}
+
+/// An alternating inference chain over any mix of links: conjugate pairs,
+/// bivalue cells and grouped nodes. One end of the chain is true, so any
+/// candidate that sees both ends is removed.
+pub struct Aic {
+    max_length: usize,
+}
+
+search_limit! {
+    /// Creates an AIC search over chains of up to 20 links.
+    Aic::new,
+    /// Sets the maximum number of links in a chain.
+    max_length = MAX_CHAIN_LENGTH,
+}
+
+impl Strategy for Aic {
+    fn name(&self) -> &'static str {
+        "AIC"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let found = Graph::new(sudoku, AIC_RULES).shortest_elimination(sudoku, self.max_length)?;
+        let name = if found.is_grouped() {
+            "Grouped AIC"
+        } else {
+            "AIC"
+        };
+        Some(chain_step(name, found))
+    }
+}
+
+/// A chain that closes on itself. A continuous loop turns each of its weak
+/// links strong; a discontinuous loop decides the candidate where it closes.
+pub struct NiceLoop {
+    max_length: usize,
+}
+
+search_limit! {
+    /// Creates a Nice Loop search over loops of up to 20 links.
+    NiceLoop::new,
+    /// Sets the maximum number of links in a loop.
+    max_length = MAX_CHAIN_LENGTH,
+}
+
+impl Strategy for NiceLoop {
+    fn name(&self) -> &'static str {
+        "Nice Loop"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let (kind, found) = Graph::new(sudoku, AIC_RULES).shortest_loop(sudoku, self.max_length)?;
+        let name = match (kind, found.is_grouped()) {
+            (LoopKind::Continuous, false) => "Continuous Nice Loop",
+            (LoopKind::Continuous, true) => "Grouped Continuous Nice Loop",
+            (LoopKind::Discontinuous, false) => "Discontinuous Nice Loop",
+            (LoopKind::Discontinuous, true) => "Grouped Discontinuous Nice Loop",
+        };
+        Some(chain_step(name, found))
+    }
+}
+
+/// Links used by general chains and loops.
+const AIC_RULES: Rules = Rules {
+    bivalue_only: false,
+    conjugate: true,
+    bivalue: true,
+    cell_weak: true,
+    groups: true,
+};
+
+/// Turns the result of a chain search into a step.
+fn chain_step(name: &'static str, found: Deduction) -> Step {
+    let Deduction {
+        chain,
+        placements,
+        eliminations,
+    } = found;
+
+    let mut step = Step::new(name);
+    step.placements = placements;
+    step.eliminations = eliminations;
+    for node in &chain.nodes {
+        step.digits.insert(node.digit);
+        for &cell in &node.cells {
+            if !step.cells.contains(&cell) {
+                step.cells.push(cell);
+            }
+        }
+    }
+    step.detail = Detail::Chain(chain);
+    step
+}
+
+/// A set of cells, stored as a bitmask of columns for each row.
+#[derive(Clone)]
+struct CellSet(Vec<u64>);
+
+impl CellSet {
+    fn new(size: usize) -> Self {
+        Self(vec![0; size])
+    }
+
+    fn insert(&mut self, (r, c): (usize, usize)) {
+        self.0[r] |= 1 << c;
+    }
+
+    fn contains(&self, (r, c): (usize, usize)) -> bool {
+        self.0[r] & (1 << c) != 0
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.iter().all(|&row| row == 0)
+    }
+
+    /// Keeps only the cells also in `other`.
+    fn intersect(&mut self, other: &Self) {
+        for (a, b) in self.0.iter_mut().zip(&other.0) {
+            *a &= b;
+        }
+    }
+
+    fn is_subset(&self, other: &Self) -> bool {
+        self.0.iter().zip(&other.0).all(|(a, b)| a & !b == 0)
+    }
+
+    fn is_disjoint(&self, other: &Self) -> bool {
+        self.0.iter().zip(&other.0).all(|(a, b)| a & b == 0)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.0.len()).flat_map(move |r| bit_indices(self.0[r]).map(move |c| (r, c)))
+    }
+}
+
+/// An almost locked set: N cells in one unit holding N + 1 candidates
+/// between them. A bivalue cell is the smallest.
+struct Als {
+    unit: Unit,
+    cells: Vec<(usize, usize)>,
+    mask: CellSet,
+    digits: Candidates,
+    /// For each digit of the set, its cells holding the digit and the cells
+    /// seeing all of those.
+    by_digit: Vec<(u8, CellSet, CellSet)>,
+}
+
+impl Als {
+    fn new(sudoku: &Sudoku, unit: Unit, cells: &[(usize, usize)], peers: &[CellSet]) -> Self {
+        let n = sudoku.size;
+        let mut mask = CellSet::new(n);
+        let mut digits = Candidates::EMPTY;
+        for &cell in cells {
+            mask.insert(cell);
+            digits |= candidates_at(sudoku, cell);
+        }
+
+        let by_digit = digits
+            .iter()
+            .map(|d| {
+                let mut holding = CellSet::new(n);
+                let mut seen = CellSet(vec![Candidates::all(n).bits(); n]);
+                for &(r, c) in cells {
+                    if sudoku.candidates(r, c).contains(d) {
+                        holding.insert((r, c));
+                        seen.intersect(&peers[r * n + c]);
+                    }
+                }
+                (d, holding, seen)
+            })
+            .collect();
+
+        Self {
+            unit,
+            cells: cells.to_vec(),
+            mask,
+            digits,
+            by_digit,
+        }
+    }
+
+    /// Returns the cells of the set holding `val` and the cells seeing all of
+    /// them. `val` must be one of the set's digits.
+    fn digit(&self, val: u8) -> (&CellSet, &CellSet) {
+        let (_, holding, seen) = self
+            .by_digit
+            .iter()
+            .find(|(d, ..)| *d == val)
+            .expect("digit of the set");
+        (holding, seen)
+    }
+}
+
+/// Returns every almost locked set of the grid, each listed once.
+fn almost_locked_sets(sudoku: &Sudoku) -> Vec<Als> {
+    let (n, bs) = (sudoku.size, sudoku.box_size);
+    let peers: Vec<_> = (0..n * n)
+        .map(|i| {
+            let mut set = CellSet::new(n);
+            for r in 0..n {
+                for c in 0..n {
+                    if sees((i / n, i % n), (r, c), bs) {
+                        set.insert((r, c));
+                    }
+                }
+            }
+            set
+        })
+        .collect();
+
+    let mut out = Vec::new();
+    for unit in units(n) {
+        let cells: Vec<_> = unit
+            .cells(bs)
+            .filter(|&cell| !candidates_at(sudoku, cell).is_empty())
+            .collect();
+        let mut chosen = Vec::new();
+        let mut found = |chosen: &[(usize, usize)]| {
+            // A set in several units is listed under the first: row, column, box.
+            if shared_units(chosen, bs).next() == Some(unit) {
+                out.push(Als::new(sudoku, unit, chosen, &peers));
+            }
+        };
+        collect_als(sudoku, &cells, Candidates::EMPTY, &mut chosen, &mut found);
+    }
+
+    out
+}
+
+/// Extends `chosen` with cells from `rest`, calling `found` on every almost
+/// locked set.
+fn collect_als(
+    sudoku: &Sudoku,
+    rest: &[(usize, usize)],
+    digits: Candidates,
+    chosen: &mut Vec<(usize, usize)>,
+    found: &mut impl FnMut(&[(usize, usize)]),
+) {
+    for (i, &cell) in rest.iter().enumerate() {
+        let digits = digits | candidates_at(sudoku, cell);
+        // Each further cell lowers the surplus of digits over cells by one at most.
+        if digits.len() > chosen.len() + 2 + (rest.len() - i - 1) {
+            continue;
+        }
+
+        chosen.push(cell);
+        if digits.len() == chosen.len() + 1 {
+            found(chosen);
+        }
+        collect_als(sudoku, &rest[i + 1..], digits, chosen, found);
+        chosen.pop();
+    }
+}
+
+/// Returns the restricted common digits of two disjoint sets: digits whose
+/// cells in one set all see their cells in the other, so at most one set can
+/// hold them.
+fn restricted_commons(a: &Als, b: &Als) -> Candidates {
+    if !a.mask.is_disjoint(&b.mask) {
+        return Candidates::EMPTY;
+    }
+
+    (a.digits & b.digits)
+        .iter()
+        .filter(|&x| b.digit(x).0.is_subset(a.digit(x).1))
+        .collect()
+}
+
+/// Returns the eliminations of `val` from every cell seeing all cells of
+/// `sets` that hold it.
+fn als_eliminations(sudoku: &Sudoku, val: u8, sets: &[&Als]) -> Vec<(usize, usize, u8)> {
+    let mut targets = sets[0].digit(val).1.clone();
+    for als in &sets[1..] {
+        targets.intersect(als.digit(val).1);
+    }
+    targets
+        .iter()
+        .filter(|&(r, c)| sudoku.candidates(r, c).contains(val))
+        .map(|(r, c)| (r, c, val))
+        .collect()
+}
+
+/// Creates an ALS step with its pattern filled in.
+fn als_step(
+    name: &'static str,
+    stem: Option<(usize, usize)>,
+    sets: &[&Als],
+    restricted: Candidates,
+    eliminations: Vec<(usize, usize, u8)>,
+) -> Step {
+    let mut step = Step::new(name);
+    for &(r, c, d) in &eliminations {
+        step.digits.insert(d);
+        if !step.eliminations.contains(&(r, c, d)) {
+            step.eliminations.push((r, c, d));
+        }
+    }
+    step.units = sets.iter().map(|als| als.unit).collect();
+    step.cells = stem
+        .into_iter()
+        .chain(sets.iter().flat_map(|als| als.cells.clone()))
+        .collect();
+    step.detail = Detail::Als {
+        stem,
+        sets: sets.iter().map(|als| als.cells.clone()).collect(),
+        restricted,
+    };
+    step
+}
+
+/// Two almost locked sets joined by a restricted common digit x: at most one
+/// of them holds x, so the other is locked and a digit z common to both must
+/// be in one of them. Cells seeing every z in either set lose z. With two
+/// restricted common digits both sets are locked.
+pub struct AlsXz;
+
+impl Strategy for AlsXz {
+    fn name(&self) -> &'static str {
+        "ALS-XZ"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let sets = almost_locked_sets(sudoku);
+
+        for (i, a) in sets.iter().enumerate() {
+            for b in &sets[i + 1..] {
+                let rcc = restricted_commons(a, b);
+                if rcc.is_empty() {
+                    continue;
+                }
+
+                let mut elims = Vec::new();
+                for z in (a.digits & b.digits) - rcc {
+                    elims.extend(als_eliminations(sudoku, z, &[a, b]));
+                }
+                if rcc.len() >= 2 {
+                    for x in rcc {
+                        elims.extend(als_eliminations(sudoku, x, &[a, b]));
+                    }
+                    for als in [a, b] {
+                        for z in als.digits - rcc {
+                            elims.extend(als_eliminations(sudoku, z, &[als]));
+                        }
+                    }
+                }
+
+                if !elims.is_empty() {
+                    return Some(als_step(self.name(), None, &[a, b], rcc, elims));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Almost locked sets A and B each joined to a third set C by different
+/// restricted common digits. C can lack only one of them, so A or B is
+/// locked and a digit z common to both must be in one of them.
+pub struct AlsXyWing;
+
+impl Strategy for AlsXyWing {
+    fn name(&self) -> &'static str {
+        "ALS-XY-Wing"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let sets = almost_locked_sets(sudoku);
+
+        for c in &sets {
+            let linked: Vec<_> = sets
+                .iter()
+                .filter_map(|als| {
+                    let rcc = restricted_commons(als, c);
+                    (!rcc.is_empty()).then_some((als, rcc))
+                })
+                .collect();
+
+            for (i, &(a, rcc_a)) in linked.iter().enumerate() {
+                for &(b, rcc_b) in &linked[i + 1..] {
+                    let common = a.digits & b.digits;
+                    for x in rcc_a {
+                        for y in rcc_b {
+                            if x == y {
+                                continue;
+                            }
+
+                            let mut elims = Vec::new();
+                            for z in common {
+                                if z != x && z != y {
+                                    elims.extend(als_eliminations(sudoku, z, &[a, b]));
+                                }
+                            }
+                            if !elims.is_empty() {
+                                let rcc = Candidates::single(x) | Candidates::single(y);
+                                return Some(als_step(self.name(), None, &[a, b, c], rcc, elims));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// A stem cell with an almost locked set (a petal) for each of its digits,
+/// where the petal's cells holding that digit all see the stem. Whichever
+/// digit the stem takes locks its petal, so a digit z in every petal must be
+/// in one of them.
+pub struct DeathBlossom;
+
+impl Strategy for DeathBlossom {
+    fn name(&self) -> &'static str {
+        "Death Blossom"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let n = sudoku.size;
+        let sets = almost_locked_sets(sudoku);
+
+        for r in 0..n {
+            for c in 0..n {
+                let stem = (r, c);
+                let digits = sudoku.candidates(r, c);
+                if digits.len() < 2 {
+                    continue;
+                }
+
+                let petals: Vec<Vec<&Als>> = digits
+                    .iter()
+                    .map(|d| {
+                        sets.iter()
+                            .filter(|als| als.digits.contains(d) && als.digit(d).1.contains(stem))
+                            .collect()
+                    })
+                    .collect();
+
+                // Each digit z outside the stem, with the cells that could lose it.
+                let targets: Vec<_> = (Candidates::all(n) - digits)
+                    .iter()
+                    .map(|z| {
+                        let mut cells = CellSet::new(n);
+                        for r in 0..n {
+                            for c in 0..n {
+                                if sudoku.candidates(r, c).contains(z) {
+                                    cells.insert((r, c));
+                                }
+                            }
+                        }
+                        (z, cells)
+                    })
+                    .filter(|(_, cells)| !cells.is_empty())
+                    .collect();
+
+                let mut chosen = Vec::new();
+                if let Some(targets) = blossom(&petals, targets, &mut chosen) {
+                    let elims = targets
+                        .iter()
+                        .flat_map(|(z, cells)| cells.iter().map(|(r, c)| (r, c, *z)))
+                        .collect();
+                    return Some(als_step(self.name(), Some(stem), &chosen, digits, elims));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Picks one petal per stem digit, narrowing each target digit's cells to
+/// those seeing all of its cells in the petals picked so far.
+fn blossom<'a>(
+    petals: &[Vec<&'a Als>],
+    targets: Vec<(u8, CellSet)>,
+    chosen: &mut Vec<&'a Als>,
+) -> Option<Vec<(u8, CellSet)>> {
+    let Some((options, rest)) = petals.split_first() else {
+        return Some(targets);
+    };
+
+    for &als in options {
+        let narrowed: Vec<_> = targets
+            .iter()
+            .filter(|(z, _)| als.digits.contains(*z))
+            .filter_map(|(z, cells)| {
+                let mut cells = cells.clone();
+                cells.intersect(als.digit(*z).1);
+                (!cells.is_empty()).then_some((*z, cells))
+            })
+            .collect();
+        if narrowed.is_empty() {
+            continue;
+        }
+
+        chosen.push(als);
+        if let Some(found) = blossom(rest, narrowed, chosen) {
+            return Some(found);
+        }
+        chosen.pop();
+    }
+
+    None
+}
+
+/// Default maximum number of rounds of singles followed from an assumption.
+const MAX_FORCING_DEPTH: usize = 12;
+
+/// Places a digit in a cell and follows only the hidden singles of that digit.
+/// If some unit is left with nowhere for the digit, the cell can't hold it.
+pub struct Nishio {
+    max_depth: usize,
+}
+
+search_limit! {
+    /// Creates a Nishio search that follows up to 12 rounds of singles.
+    Nishio::new,
+    /// Sets the maximum number of rounds of singles followed.
+    max_depth = MAX_FORCING_DEPTH,
+}
+
+impl Strategy for Nishio {
+    fn name(&self) -> &'static str {
+        "Nishio"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let n = sudoku.size;
+
+        for val in 1..=n as u8 {
+            for r in 0..n {
+                for c in 0..n {
+                    if !sudoku.candidates(r, c).contains(val) {
+                        continue;
+                    }
+
+                    let prop = Propagation::assume(sudoku, (r, c), val, Some(val), self.max_depth);
+                    if let Some(branch) = prop.contradiction() {
+                        let mut step = Step::new(self.name())
+                            .eliminate(r, c, val)
+                            .cell(r, c)
+                            .digit(val);
+                        step.detail = Detail::Forcing(vec![branch]);
+                        return Some(step);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Tries every candidate of one cell in turn. Whatever follows from all of
+/// them, through chains of singles, must be true.
+pub struct CellForcingChain {
+    max_depth: usize,
+}
+
+search_limit! {
+    /// Creates a cell forcing chain search that follows up to 12 rounds of
+    /// singles from each candidate.
+    CellForcingChain::new,
+    /// Sets the maximum number of rounds of singles followed per candidate.
+    max_depth = MAX_FORCING_DEPTH,
+}
+
+impl Strategy for CellForcingChain {
+    fn name(&self) -> &'static str {
+        "Cell Forcing Chain"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        for count in 2..=sudoku.size {
+            for (r, c) in cells_by_count(sudoku, count) {
+                let branches: Vec<_> = sudoku
+                    .candidates(r, c)
+                    .iter()
+                    .map(|v| Propagation::assume(sudoku, (r, c), v, None, self.max_depth))
+                    .collect();
+
+                if let Some(step) = forcing_step(self.name(), sudoku, &branches) {
+                    return Some(step.cell(r, c));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Tries every place for a digit in one unit in turn. Whatever follows from
+/// all of them, through chains of singles, must be true.
+pub struct UnitForcingChain {
+    max_depth: usize,
+}
+
+search_limit! {
+    /// Creates a unit forcing chain search that follows up to 12 rounds of
+    /// singles from each place.
+    UnitForcingChain::new,
+    /// Sets the maximum number of rounds of singles followed per place.
+    max_depth = MAX_FORCING_DEPTH,
+}
+
+impl Strategy for UnitForcingChain {
+    fn name(&self) -> &'static str {
+        "Unit Forcing Chain"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let n = sudoku.size;
+
+        for count in 2..=n {
+            for unit in units(n) {
+                for val in 1..=n as u8 {
+                    let places = cells_with(sudoku, unit, val);
+                    if places.len() != count {
+                        continue;
+                    }
+
+                    let branches: Vec<_> = places
+                        .iter()
+                        .map(|&cell| Propagation::assume(sudoku, cell, val, None, self.max_depth))
+                        .collect();
+
+                    if let Some(mut step) = forcing_step(self.name(), sudoku, &branches) {
+                        step.units.push(unit);
+                        step.cells = places;
+                        return Some(step);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Finds a conclusion shared by every branch that doesn't end in a
+/// contradiction, preferring the one with the shortest proof.
+fn forcing_step(name: &'static str, sudoku: &Sudoku, branches: &[Propagation]) -> Option<Step> {
+    let n = sudoku.size;
+    let consistent: Vec<_> = branches.iter().filter(|b| !b.is_contradiction()).collect();
+    let first = consistent.first()?;
+
+    // Each conclusion with the proof from every branch, in branch order.
+    let proofs = |prove: &dyn Fn(&Propagation) -> Option<Branch>| -> Option<Vec<Branch>> {
+        branches
+            .iter()
+            .map(|b| b.contradiction().or_else(|| prove(b)))
+            .collect()
+    };
+    let mut best: Option<(usize, Step)> = None;
+    let mut consider = |step: Step, proof: Option<Vec<Branch>>| {
+        let Some(proof) = proof else {
+            return;
+        };
+        let len = proof.iter().map(|b| b.placements.len()).sum();
+        if best.as_ref().is_none_or(|(l, _)| len < *l) {
+            let mut step = step;
+            for branch in &proof {
+                step.digits.insert(branch.placements[0].2);
+            }
+            step.detail = Detail::Forcing(proof);
+            best = Some((len, step));
+        }
+    };
+
+    for &(r, c, v) in first.facts() {
+        let proof = proofs(&|b| b.placement((r, c), v));
+        consider(Step::new(name).place(r, c, v), proof);
+    }
+    for r in 0..n {
+        for c in 0..n {
+            for v in sudoku.candidates(r, c) {
+                if first.elimination((r, c), v).is_some() {
+                    let proof = proofs(&|b| b.elimination((r, c), v));
+                    consider(Step::new(name).eliminate(r, c, v), proof);
+                }
+            }
+        }
+    }
+
+    best.map(|(_, step)| step)
+}
+
+/// The corners of a rectangle, in the order (r1, c1), (r1, c2), (r2, c1),
+/// (r2, c2). Corner `i` is diagonal to corner `3 - i`.
+type Corners = [(usize, usize); 4];
+
+/// Finds one type of unique rectangle among the given corners and digits.
+type RectangleType = fn(&Sudoku, &Corners, [u8; 2]) -> Option<Step>;
+
+/// Avoids a deadly pattern: four empty cells at the corners of a rectangle
+/// spanning two boxes, all holding the same two digits. If the rectangle were
+/// left with only those digits they could be swapped, giving two solutions, so
+/// one of its extra candidates must be true.
+///
+/// Only valid for puzzles with a unique solution.
+pub struct UniqueRectangle;
+
+impl Strategy for UniqueRectangle {
+    fn name(&self) -> &'static str {
+        "Unique Rectangle"
+    }
+
+    fn requires_uniqueness(&self) -> bool {
+        true
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let rectangles = rectangles(sudoku);
+        let types: [RectangleType; 6] = [
+            rectangle_type1,
+            rectangle_type2,
+            rectangle_type3,
+            rectangle_type4,
+            rectangle_type5,
+            rectangle_type6,
+        ];
+
+        types.iter().find_map(|find| {
+            rectangles
+                .iter()
+                .find_map(|(corners, pair)| find(sudoku, corners, *pair))
+        })
+    }
+}
+
+/// Returns every rectangle of empty cells spanning two boxes, once for each
+/// pair of digits all four corners share.
+fn rectangles(sudoku: &Sudoku) -> Vec<(Corners, [u8; 2])> {
+    let (n, bs) = (sudoku.size, sudoku.box_size);
+    let mut out = Vec::new();
+
+    for r1 in 0..n {
+        for r2 in r1 + 1..n {
+            for c1 in 0..n {
+                for c2 in c1 + 1..n {
+                    // Two boxes: the rows share a band or the columns share a
+                    // stack, but not both.
+                    if (r1 / bs == r2 / bs) == (c1 / bs == c2 / bs) {
+                        continue;
+                    }
+
+                    let corners = [(r1, c1), (r1, c2), (r2, c1), (r2, c2)];
+                    let common = corners.iter().fold(Candidates::all(n), |acc, &cell| {
+                        acc & candidates_at(sudoku, cell)
+                    });
+                    for a in common {
+                        for b in common {
+                            if a < b {
+                                out.push((corners, [a, b]));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    out
+}
+
+/// Returns the corners holding candidates other than the rectangle's digits.
+fn roof(sudoku: &Sudoku, corners: &Corners, pair: [u8; 2]) -> Vec<usize> {
+    (0..4)
+        .filter(|&i| !extras(sudoku, corners[i], pair).is_empty())
+        .collect()
+}
+
+/// Returns the candidates of a corner other than the rectangle's digits.
+fn extras(sudoku: &Sudoku, cell: (usize, usize), pair: [u8; 2]) -> Candidates {
+    candidates_at(sudoku, cell) - pair.into_iter().collect()
+}
+
+/// Returns true if two corners share a row or column.
+#[inline]
+fn adjacent(i: usize, j: usize) -> bool {
+    i ^ j != 3
+}
+
+/// Creates a unique rectangle step with its pattern filled in.
+fn rectangle_step(name: &'static str, corners: &Corners, [a, b]: [u8; 2]) -> Step {
+    let mut step = Step::new(name).digit(a).digit(b);
+    step.cells = corners.to_vec();
+    step
+}
+
+/// Type 1: only one corner has extra candidates, so it can't hold either digit.
+fn rectangle_type1(sudoku: &Sudoku, corners: &Corners, pair: [u8; 2]) -> Option<Step> {
+    let &[i] = roof(sudoku, corners, pair).as_slice() else {
+        return None;
+    };
+
+    let (r, c) = corners[i];
+    let mut step = rectangle_step("Unique Rectangle Type 1", corners, pair);
+    step.eliminations = pair.iter().map(|&d| (r, c, d)).collect();
+    Some(step)
+}
+
+/// Type 2: two corners in a line share a single extra candidate; one of them
+/// holds it, so cells seeing both lose it.
+fn rectangle_type2(sudoku: &Sudoku, corners: &Corners, pair: [u8; 2]) -> Option<Step> {
+    let &[i, j] = roof(sudoku, corners, pair).as_slice() else {
+        return None;
+    };
+    let extra = extras(sudoku, corners[i], pair);
+    if !adjacent(i, j) || extra != extras(sudoku, corners[j], pair) {
+        return None;
+    }
+
+    let val = extra.single_value()?;
+    let roof = [corners[i], corners[j]];
+    let mut step = rectangle_step("Unique Rectangle Type 2", corners, pair);
+    step.eliminations = cells_seeing_all(sudoku, val, &roof);
+    (!step.eliminations.is_empty()).then_some(step)
+}
+
+/// Type 3: the extra candidates of two corners in a line act as one cell,
+/// forming a naked subset with other cells of a unit they share.
+fn rectangle_type3(sudoku: &Sudoku, corners: &Corners, pair: [u8; 2]) -> Option<Step> {
+    let bs = sudoku.box_size;
+    let &[i, j] = roof(sudoku, corners, pair).as_slice() else {
+        return None;
+    };
+    if !adjacent(i, j) {
+        return None;
+    }
+
+    let roof = [corners[i], corners[j]];
+    let extra = extras(sudoku, roof[0], pair) | extras(sudoku, roof[1], pair);
+
+    for unit in shared_units(&roof, bs) {
+        let others: Vec<_> = unit
+            .cells(bs)
+            .filter(|&cell| !roof.contains(&cell) && !candidates_at(sudoku, cell).is_empty())
+            .collect();
+
+        for k in 1..others.len() {
+            let found = find_combination(others.len(), k, |idx| {
+                let subset = idx
+                    .iter()
+                    .fold(extra, |acc, &x| acc | candidates_at(sudoku, others[x]));
+                if subset.len() != k + 1 {
+                    return None;
+                }
+
+                let mut step = rectangle_step("Unique Rectangle Type 3", corners, pair).unit(unit);
+                for (x, &(r, c)) in others.iter().enumerate() {
+                    if idx.contains(&x) {
+                        step.cells.push((r, c));
+                        continue;
+                    }
+                    for d in sudoku.candidates(r, c) & subset {
+                        step.eliminations.push((r, c, d));
+                    }
+                }
+                (!step.eliminations.is_empty()).then_some(step)
+            });
+            if found.is_some() {
+                return found;
+            }
+        }
+    }
+
+    None
+}
+
+/// Type 4: two corners in a line are the only places for one digit in a unit
+/// they share, so neither can hold the other digit.
+fn rectangle_type4(sudoku: &Sudoku, corners: &Corners, pair: [u8; 2]) -> Option<Step> {
+    let bs = sudoku.box_size;
+    let &[i, j] = roof(sudoku, corners, pair).as_slice() else {
+        return None;
+    };
+    if !adjacent(i, j) {
+        return None;
+    }
+
+    let roof = [corners[i], corners[j]];
+    for unit in shared_units(&roof, bs) {
+        for (x, y) in [(pair[0], pair[1]), (pair[1], pair[0])] {
+            if cells_with(sudoku, unit, x).len() == 2 {
+                let mut step = rectangle_step("Unique Rectangle Type 4", corners, pair).unit(unit);
+                step.eliminations = roof.iter().map(|&(r, c)| (r, c, y)).collect();
+                return Some(step);
+            }
+        }
+    }
+
+    None
+}
+
+/// Type 5: two diagonal corners, or three corners, share a single extra
+/// candidate; one of them holds it, so cells seeing them all lose it.
+fn rectangle_type5(sudoku: &Sudoku, corners: &Corners, pair: [u8; 2]) -> Option<Step> {
+    let roof_idx = roof(sudoku, corners, pair);
+    match *roof_idx.as_slice() {
+        [i, j] if !adjacent(i, j) => {}
+        [_, _, _] => {}
+        _ => return None,
+    }
+
+    let roof: Vec<_> = roof_idx.iter().map(|&i| corners[i]).collect();
+    let extra = extras(sudoku, roof[0], pair);
+    if roof.iter().any(|&cell| extras(sudoku, cell, pair) != extra) {
+        return None;
+    }
+
+    let val = extra.single_value()?;
+    let mut step = rectangle_step("Unique Rectangle Type 5", corners, pair);
+    step.eliminations = cells_seeing_all(sudoku, val, &roof);
+    (!step.eliminations.is_empty()).then_some(step)
+}
+
+/// Type 6: two diagonal corners have extra candidates, and one digit is
+/// confined to the rectangle in both of its rows or both of its columns. That
+/// digit must then sit in the other two corners.
+fn rectangle_type6(sudoku: &Sudoku, corners: &Corners, pair: [u8; 2]) -> Option<Step> {
+    let &[i, j] = roof(sudoku, corners, pair).as_slice() else {
+        return None;
+    };
+    if adjacent(i, j) {
+        return None;
+    }
+
+    let (r1, c1) = corners[0];
+    let (r2, c2) = corners[3];
+    for val in pair {
+        for lines in [
+            [Unit::Row(r1), Unit::Row(r2)],
+            [Unit::Col(c1), Unit::Col(c2)],
+        ] {
+            if lines
+                .iter()
+                .all(|&line| cells_with(sudoku, line, val).len() == 2)
+            {
+                let mut step = rectangle_step("Unique Rectangle Type 6", corners, pair)
+                    .unit(lines[0])
+                    .unit(lines[1]);
+                step.eliminations = [corners[i], corners[j]]
+                    .iter()
+                    .map(|&(r, c)| (r, c, val))
+                    .collect();
+                return Some(step);
+            }
+        }
+    }
+
+    None
+}
+
+/// Bivalue Universal Grave plus one: every empty cell but one has exactly two
+/// candidates. Without the digit that appears three times in the odd cell's
+/// units, every digit would appear twice in every unit, a pattern with either
+/// no solution or two, so that digit must go there.
+///
+/// Only valid for puzzles with a unique solution.
+pub struct BugPlusOne;
+
+impl Strategy for BugPlusOne {
+    fn name(&self) -> &'static str {
+        "BUG+1"
+    }
+
+    fn requires_uniqueness(&self) -> bool {
+        true
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let n = sudoku.size;
+        let mut odd = None;
+
+        for r in 0..n {
+            for c in 0..n {
+                match sudoku.candidates(r, c).len() {
+                    0 | 2 => {}
+                    3 if odd.is_none() => odd = Some((r, c)),
+                    _ => return None,
+                }
+            }
+        }
+
+        let (r, c) = odd?;
+        let bs = sudoku.box_size;
+        for val in sudoku.candidates(r, c) {
+            let cands = |cell: (usize, usize)| {
+                let mut cands = candidates_at(sudoku, cell);
+                if cell == (r, c) {
+                    cands.remove(val);
+                }
+                cands
+            };
+
+            let grave = units(n).all(|unit| {
+                (1..=n as u8).all(|d| {
+                    let count = unit.cells(bs).filter(|&x| cands(x).contains(d)).count();
+                    count == 0 || count == 2
+                })
+            });
+            if grave {
+                return Some(Step::new(self.name()).place(r, c, val).cell(r, c));
+            }
+        }
+
+        None
+    }
+}
+
+/// Fills in the pattern of a coloring step from its clusters.
+fn coloring_step(mut step: Step, clusters: Vec<[Vec<(usize, usize)>; 2]>) -> Step {
+    step.cells = clusters.iter().flatten().flatten().copied().collect();
+    step.detail = Detail::Coloring(clusters);
+    step
+}
+
+/// Splits the cells joined by strong links on `val` into clusters, each
+/// two-colored so that the ends of every link get opposite colors.
+fn clusters(sudoku: &Sudoku, val: u8) -> Vec<[Vec<(usize, usize)>; 2]> {
+    let n = sudoku.size;
+    let links = strong_links(sudoku, val);
+    let mut color: Vec<Option<usize>> = vec![None; n * n];
+    let mut clusters = Vec::new();
+
+    for &(_, start, _) in &links {
+        if color[start.0 * n + start.1].is_some() {
+            continue;
+        }
+
+        let mut classes = [vec![start], Vec::new()];
+        let mut queue = vec![(start, 0)];
+        let mut consistent = true;
+        color[start.0 * n + start.1] = Some(0);
+
+        while let Some((cell, k)) = queue.pop() {
+            for &(_, a, b) in &links {
+                let next = match cell {
+                    _ if a == cell => b,
+                    _ if b == cell => a,
+                    _ => continue,
+                };
+                match color[next.0 * n + next.1] {
+                    Some(existing) => consistent &= existing != k,
+                    None => {
+                        color[next.0 * n + next.1] = Some(1 - k);
+                        classes[1 - k].push(next);
+                        queue.push((next, 1 - k));
+                    }
+                }
+            }
+        }
+
+        // An odd cycle of strong links means the grid is already broken.
+        if consistent {
+            classes.iter_mut().for_each(|c| c.sort_unstable());
+            clusters.push(classes);
+        }
+    }
+
+    clusters
+}
+
+/// Returns the eliminations of `val` from every cell that sees all of `cells`.
+fn cells_seeing_all(sudoku: &Sudoku, val: u8, cells: &[(usize, usize)]) -> Vec<(usize, usize, u8)> {
+    let bs = sudoku.box_size;
+    let mut elims = Vec::new();
+    for r in 0..sudoku.size {
+        for c in 0..sudoku.size {
+            if sudoku.candidates(r, c).contains(val) && cells.iter().all(|&x| sees((r, c), x, bs)) {
+                elims.push((r, c, val));
+            }
+        }
+    }
+    elims
+}
+
+/// Returns true if some cell of `a` sees some cell of `b`.
+fn any_sees(a: &[(usize, usize)], b: &[(usize, usize)], bs: usize) -> bool {
+    a.iter().any(|&x| b.iter().any(|&y| sees(x, y, bs)))
+}
+
+/// Returns the eliminations of `val` from cells outside `a` and `b` that see a
+/// cell of each.
+fn cells_seeing_both(
+    sudoku: &Sudoku,
+    val: u8,
+    a: &[(usize, usize)],
+    b: &[(usize, usize)],
+    bs: usize,
+) -> Vec<(usize, usize, u8)> {
+    let mut elims = Vec::new();
+    for r in 0..sudoku.size {
+        for c in 0..sudoku.size {
+            let cell = (r, c);
+            if sudoku.candidates(r, c).contains(val)
+                && !a.contains(&cell)
+                && !b.contains(&cell)
+                && a.iter().any(|&x| sees(cell, x, bs))
+                && b.iter().any(|&y| sees(cell, y, bs))
+            {
+                elims.push((r, c, val));
+            }
+        }
+    }
+    elims
+}
+
+/// Builds a step for a chain of two strong links: `chain` is the four cells in
+/// order, and `val` goes from every cell seeing both ends.
+fn link_step(
+    name: &'static str,
+    sudoku: &Sudoku,
+    val: u8,
+    chain: &[(usize, usize); 4],
+    links: &[Unit],
+) -> Option<Step> {
+    let bs = sudoku.box_size;
+    let (start, end) = (chain[0], chain[3]);
+    let mut step = Step::new(name).digit(val);
+
+    for r in 0..sudoku.size {
+        for c in 0..sudoku.size {
+            if sudoku.candidates(r, c).contains(val)
+                && !chain.contains(&(r, c))
+                && sees((r, c), start, bs)
+                && sees((r, c), end, bs)
+            {
+                step.eliminations.push((r, c, val));
+            }
+        }
+    }
+    if step.eliminations.is_empty() {
+        return None;
+    }
+
+    step.cells = chain.to_vec();
+    step.units = links.to_vec();
+    Some(step)
+}
+
+/// Builds a wing step eliminating `val` from every cell that sees all of `seen`.
+fn wing_step(
+    name: &'static str,
+    sudoku: &Sudoku,
+    val: u8,
+    pivot: &[(usize, usize)],
+    pincers: &[(usize, usize)],
+    seen: &[(usize, usize)],
+) -> Option<Step> {
+    let bs = sudoku.box_size;
+    let mut step = Step::new(name);
+
+    for r in 0..sudoku.size {
+        for c in 0..sudoku.size {
+            if sudoku.candidates(r, c).contains(val) && seen.iter().all(|&s| sees((r, c), s, bs)) {
+                step.eliminations.push((r, c, val));
+            }
+        }
+    }
+    if step.eliminations.is_empty() {
+        return None;
+    }
+
+    step.cells = pivot.iter().chain(pincers).copied().collect();
+    step.digits = pincers.iter().fold(Candidates::EMPTY, |acc, &cell| {
+        acc | candidates_at(sudoku, cell)
+    });
+    step.detail = Detail::Wing {
+        pivot: pivot.to_vec(),
+        pincers: pincers.to_vec(),
+    };
+    Some(step)
+}
+
+/// Eliminates a value from a line when, within a box, it is confined to that line.
+pub struct Pointing;
+
+impl Strategy for Pointing {
+    fn name(&self) -> &'static str {
+        "Pointing"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let n = sudoku.size;
+
+        for b in 0..n {
+            let base = Unit::Box(b);
+            for val in 1..=n as u8 {
+                let cells = cells_with(sudoku, base, val);
+                let Some(&(r, c)) = cells.first() else {
+                    continue;
+                };
+
+                for cover in [Unit::Row(r), Unit::Col(c)] {
+                    if let Some(step) = locked(self.name(), sudoku, base, cover, &cells, val) {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Eliminates a value from a box when, within a line, it is confined to that box.
+pub struct Claiming;
+
+impl Strategy for Claiming {
+    fn name(&self) -> &'static str {
+        "Claiming"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let n = sudoku.size;
+        let lines = (0..n).map(Unit::Row).chain((0..n).map(Unit::Col));
+
+        for base in lines {
+            for val in 1..=n as u8 {
+                let cells = cells_with(sudoku, base, val);
+                let Some(&(r, c)) = cells.first() else {
+                    continue;
+                };
+
+                let cover = Unit::Box(sudoku.box_index(r, c));
+                if let Some(step) = locked(self.name(), sudoku, base, cover, &cells, val) {
+                    return Some(step);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Builds a locked candidates step if all `cells` of `base` holding `val` also
+/// lie in `cover`, and `cover` has other cells to eliminate from.
+fn locked(
+    name: &'static str,
+    sudoku: &Sudoku,
+    base: Unit,
+    cover: Unit,
+    cells: &[(usize, usize)],
+    val: u8,
+) -> Option<Step> {
+    let bs = sudoku.box_size;
+    if cells.len() < 2 || !cells.iter().all(|&(r, c)| cover.contains(r, c, bs)) {
+        return None;
+    }
+
+    let targets: Vec<_> = cells_with(sudoku, cover, val)
+        .into_iter()
+        .filter(|&(r, c)| !base.contains(r, c, bs))
+        .collect();
+    if targets.is_empty() {
+        return None;
+    }
+
+    let mut step = Step::new(name).unit(base).unit(cover).digit(val);
+    step.cells = cells.to_vec();
+    step.eliminations = targets.into_iter().map(|(r, c)| (r, c, val)).collect();
+    Some(step)
+}
+
+/// Sue de Coq: two or three cells where a box meets a line, holding exactly
+/// two more digits than there are cells, together with cells elsewhere in the
+/// line and elsewhere in the box whose digits are disjoint subsets of those.
+/// When the three groups have as many cells as digits, each digit goes in
+/// exactly one group: digits not in the box group stay in the line, and digits
+/// not in the line group stay in the box.
+pub struct SueDeCoq;
+
+impl Strategy for SueDeCoq {
+    fn name(&self) -> &'static str {
+        "Sue de Coq"
+    }
+
+    fn find_step(&self, sudoku: &Sudoku) -> Option<Step> {
+        let (n, bs) = (sudoku.size, sudoku.box_size);
+
+        for b in 0..n {
+            let rows = (0..bs).map(|i| Unit::Row(b / bs * bs + i));
+            let cols = (0..bs).map(|i| Unit::Col(b % bs * bs + i));
+
+            for line in rows.chain(cols) {
+                let boxed = Unit::Box(b);
+                let empty = |&(r, c): &(usize, usize)| !sudoku.candidates(r, c).is_empty();
+                let (core, line_rest): (Vec<_>, Vec<_>) = line
+                    .cells(bs)
+                    .filter(empty)
+                    .partition(|&(r, c)| boxed.contains(r, c, bs));
+                let box_rest: Vec<_> = boxed
+                    .cells(bs)
+                    .filter(|&(r, c)| !line.contains(r, c, bs))
+                    .filter(empty)
+                    .collect();
+
+                // Larger cores, possible from 16x16 up, make the search
+                // explode for little gain.
+                for k in 2..=core.len().min(3) {
+                    let found = find_combination(core.len(), k, |idx| {
+                        let cells: Vec<_> = idx.iter().map(|&i| core[i]).collect();
+                        sue_de_coq(sudoku, boxed, line, &cells, &line_rest, &box_rest)
+                    });
+                    if found.is_some() {
+                        return found;
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Looks for line and box groups completing a Sue de Coq around `core`.
+fn sue_de_coq(
+    sudoku: &Sudoku,
+    boxed: Unit,
+    line: Unit,
+    core: &[(usize, usize)],
+    line_rest: &[(usize, usize)],
+    box_rest: &[(usize, usize)],
+) -> Option<Step> {
+    let bs = sudoku.box_size;
+    let digits = core.iter().fold(Candidates::EMPTY, |acc, &cell| {
+        acc | candidates_at(sudoku, cell)
+    });
+    if digits.len() != core.len() + 2 {
+        return None;
+    }
+
+    let within = |cells: &[(usize, usize)]| -> Vec<(usize, usize)> {
+        cells
+            .iter()
+            .copied()
+            .filter(|&cell| (candidates_at(sudoku, cell) - digits).is_empty())
+            .collect()
+    };
+    let (line_pool, box_pool) = (within(line_rest), within(box_rest));
+    let union = |pool: &[(usize, usize)], idx: &[usize]| {
+        idx.iter().fold(Candidates::EMPTY, |acc, &i| {
+            acc | candidates_at(sudoku, pool[i])
+        })
+    };
+
+    let need = digits.len() - core.len();
+    for d_len in 1..need {
+        let found = find_combination(line_pool.len(), d_len, |di| {
+            let d_digits = union(&line_pool, di);
+            find_combination(box_pool.len(), need - d_len, |ei| {
+                let e_digits = union(&box_pool, ei);
+                if !(d_digits & e_digits).is_empty() {
+                    return None;
+                }
+
+                let mut step = Step::new("Sue de Coq").unit(boxed).unit(line);
+                step.digits = digits;
+                step.cells = core.to_vec();
+                step.cells.extend(di.iter().map(|&i| line_pool[i]));
+                step.cells.extend(ei.iter().map(|&i| box_pool[i]));
+
+                for (unit, keep) in [(line, digits - e_digits), (boxed, digits - d_digits)] {
+                    for (r, c) in unit.cells(bs) {
+                        if step.cells.contains(&(r, c)) {
+                            continue;
+                        }
+                        for v in sudoku.candidates(r, c) & keep {
+                            step.eliminations.push((r, c, v));
+                        }
+                    }
+                }
+                (!step.eliminations.is_empty()).then_some(step)
+            })
+        });
+        if found.is_some() {
+            return found;
+        }
+    }
+
+    None
+}
+
+/// Returns the cells of a unit that still have `val` as a candidate.
+fn cells_with(sudoku: &Sudoku, unit: Unit, val: u8) -> Vec<(usize, usize)> {
+    unit.cells(sudoku.box_size)
+        .filter(|&(r, c)| sudoku.candidates(r, c).contains(val))
+        .collect()
+}
+
+/// Constructor for one kind of unit, e.g. `Unit::Row`.
+type UnitKind = fn(usize) -> Unit;
+
+/// Returns a bitmask of the positions within `unit` that have `val` as a candidate.
+fn line_mask(sudoku: &Sudoku, unit: Unit, val: u8) -> u64 {
+    (0..sudoku.size)
+        .filter(|&i| {
+            let (r, c) = unit.cell(i, sudoku.box_size);
+            sudoku.candidates(r, c).contains(val)
+        })
+        .fold(0, |acc, i| acc | 1 << i)
+}
+
+/// Iterates over the indices of the set bits of `mask`, lowest first.
+fn bit_indices(mask: u64) -> impl Iterator<Item = usize> {
+    Candidates::from_bits(mask).iter().map(|v| v as usize - 1)
+}
+
+/// Returns the empty cells with exactly `count` candidates.
+fn cells_by_count(sudoku: &Sudoku, count: usize) -> Vec<(usize, usize)> {
+    (0..sudoku.size)
+        .flat_map(|r| (0..sudoku.size).map(move |c| (r, c)))
+        .filter(|&(r, c)| sudoku.candidates(r, c).len() == count)
+        .collect()
+}
+
+#[inline]
+pub(crate) fn candidates_at(sudoku: &Sudoku, (r, c): (usize, usize)) -> Candidates {
+    sudoku.candidates(r, c)
+}
+
+/// Returns true if two distinct cells share a row, column or box.
+#[inline]
+pub(crate) fn sees(a: (usize, usize), b: (usize, usize), bs: usize) -> bool {
+    a != b && (a.0 == b.0 || a.1 == b.1 || same_box(a, b, bs))
+}
+
+/// A strong link: a unit together with the only two cells holding a digit there.
+type Link = (Unit, (usize, usize), (usize, usize));
+
+/// Returns the strong links on `val`: units where it has exactly two candidates.
+fn strong_links(sudoku: &Sudoku, val: u8) -> Vec<Link> {
+    units(sudoku.size)
+        .filter_map(|unit| match cells_with(sudoku, unit, val).as_slice() {
+            &[a, b] => Some((unit, a, b)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[inline]
+fn same_box(a: (usize, usize), b: (usize, usize), bs: usize) -> bool {
+    a.0 / bs == b.0 / bs && a.1 / bs == b.1 / bs
+}
+
+/// Iterates over all rows, then columns, then boxes.
+pub(crate) fn units(n: usize) -> impl Iterator<Item = Unit> {
+    (0..n)
+        .map(Unit::Row)
+        .chain((0..n).map(Unit::Col))
+        .chain((0..n).map(Unit::Box))
+}
+
+/// Returns the units that contain every one of `cells`.
+fn shared_units(cells: &[(usize, usize)], bs: usize) -> impl Iterator<Item = Unit> + '_ {
+    let (r, c) = cells[0];
+    [Unit::Row(r), Unit::Col(c), Unit::Box(r / bs * bs + c / bs)]
+        .into_iter()
+        .filter(move |u| cells.iter().all(|&(r, c)| u.contains(r, c, bs)))
+}
+
+/// Calls `f` with every `k`-element combination of `0..n` in lexicographic
+/// order, returning the first `Some` it produces.
+fn find_combination<T>(n: usize, k: usize, mut f: impl FnMut(&[usize]) -> Option<T>) -> Option<T> {
+    if k == 0 || k > n {
+        return None;
+    }
+
+    let mut idx: Vec<usize> = (0..k).collect();
+    loop {
+        if let Some(found) = f(&idx) {
+            return Some(found);
+        }
+
+        // Advance the rightmost index that still has room to move.
+        let mut i = k;
+        while i > 0 && idx[i - 1] == n - k + i - 1 {
+            i -= 1;
+        }
+        if i == 0 {
+            return None;
+        }
+        idx[i - 1] += 1;
+        for j in i..k {
+            idx[j] = idx[j - 1] + 1;
+        }
+    }
 }
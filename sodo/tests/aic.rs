@@ -0,0 +1,95 @@
+mod common;
+
+use common::{Marks, col, except, row, sorted, step};
+use sodo::strategy::{Aic, NiceLoop};
+use sodo::{Chain, Detail, Link, Node};
+
+fn chain(step: &sodo::Step) -> &Chain {
+    match &step.detail {
+        Detail::Chain(chain) => chain,
+        other => panic!("expected a chain, got {other:?}"),
+    }
+}
+
+#[test]
+fn aic_mixing_bivalue_cells_and_a_conjugate_pair() {
+    // {4,7} at (2,3) and (5,5), with 7 confined to rows 2 and 5 in column 8.
+    let marks = Marks::new()
+        .cell((1, 2), "47")
+        .cell((4, 4), "47")
+        .without(7, &except(col(7), &[(1, 7), (4, 7)]));
+
+    let step = step(Aic::new(), &marks);
+    assert_eq!(step.strategy, "AIC");
+    let text = chain(&step).to_string();
+    assert!(
+        [
+            "(4=7)r2c3-(7)r2c8=(7)r5c8-(7=4)r5c5",
+            "(4=7)r5c5-(7)r5c8=(7)r2c8-(7=4)r2c3"
+        ]
+        .contains(&text.as_str()),
+        "got {text}"
+    );
+    assert_eq!(sorted(step.eliminations), [(1, 4, 4), (4, 2, 4)]);
+}
+
+#[test]
+fn continuous_nice_loop() {
+    // Two {1,3} cells in row 1 loop back on each other.
+    let marks = Marks::new().cells(&[(0, 4), (0, 7)], "13");
+
+    let step = step(NiceLoop::new(), &marks);
+    assert_eq!(step.strategy, "Continuous Nice Loop");
+    assert_eq!(chain(&step).to_string(), "(1=3)r1c5-(3=1)r1c8-(1)r1c5");
+    let others = except(row(0), &[(0, 4), (0, 7)]);
+    let mut expected: Vec<_> = others
+        .iter()
+        .flat_map(|&(r, c)| [(r, c, 1), (r, c, 3)])
+        .collect();
+    expected.sort_unstable();
+    assert_eq!(sorted(step.eliminations), expected);
+}
+
+#[test]
+fn discontinuous_nice_loop() {
+    // Placing 1 at (1,1) would force (1,5) to 4, (5,5) to 7 and (5,1) to 1,
+    // in the same column.
+    let marks = Marks::new()
+        .cell((0, 4), "14")
+        .cell((4, 4), "47")
+        .cell((4, 0), "17");
+
+    let step = step(NiceLoop::new(), &marks);
+    assert_eq!(step.strategy, "Discontinuous Nice Loop");
+    assert_eq!(
+        chain(&step).to_string(),
+        "(1)r1c1-(1=4)r1c5-(4=7)r5c5-(7=1)r5c1-(1)r1c1"
+    );
+    assert_eq!(step.eliminations, [(0, 0, 1)]);
+}
+
+#[test]
+fn eureka_notation() {
+    let chain = Chain {
+        nodes: vec![
+            Node::cell(1, 2, 4),
+            Node::cell(1, 2, 7),
+            Node::cell(1, 7, 7),
+            Node::cell(1, 7, 1),
+        ],
+        links: vec![Link::Strong, Link::Weak, Link::Strong],
+    };
+    assert_eq!(chain.to_string(), "(4=7)r2c3-(7=1)r2c8");
+
+    let grouped = Chain {
+        nodes: vec![
+            Node {
+                cells: vec![(0, 6), (0, 7)],
+                digit: 5,
+            },
+            Node::cell(0, 2, 5),
+        ],
+        links: vec![Link::Strong],
+    };
+    assert_eq!(grouped.to_string(), "(5)r1c78=(5)r1c3");
+}
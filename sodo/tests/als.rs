@@ -0,0 +1,70 @@
+mod common;
+
+use common::{Marks, sorted, step};
+use sodo::Detail;
+use sodo::strategy::{AlsXyWing, AlsXz, DeathBlossom};
+
+#[test]
+fn als_xz() {
+    // (1,1) {1,2} and the row 5 set (5,1) {2,3}, (5,2) {1,3} can't both hold
+    // 2, so one of them is locked and holds 1.
+    let marks = Marks::new()
+        .cell((0, 0), "12")
+        .cell((4, 0), "23")
+        .cell((4, 1), "13");
+
+    let step = step(AlsXz, &marks);
+    let Detail::Als {
+        sets, restricted, ..
+    } = &step.detail
+    else {
+        panic!("expected sets, got {:?}", step.detail);
+    };
+    assert_eq!(sets, &[vec![(0, 0)], vec![(4, 0), (4, 1)]]);
+    assert_eq!(restricted.iter().collect::<Vec<_>>(), [2]);
+    assert_eq!(
+        sorted(step.eliminations),
+        [(0, 1, 1), (1, 1, 1), (2, 1, 1), (3, 0, 1), (5, 0, 1)]
+    );
+}
+
+#[test]
+fn als_xy_wing() {
+    // (1,5) {1,2} shares 1 with the row 1 set (1,1) {1,3,5}, (1,2) {3,5}, and
+    // 2 with (3,5) {2,3}. It can't lose both, so one of them holds 3.
+    let marks = Marks::new()
+        .cell((0, 4), "12")
+        .cell((0, 0), "135")
+        .cell((0, 1), "35")
+        .cell((2, 4), "23");
+
+    let step = step(AlsXyWing, &marks);
+    let Detail::Als { sets, .. } = &step.detail else {
+        panic!("expected sets, got {:?}", step.detail);
+    };
+    assert_eq!(sets, &[vec![(0, 0), (0, 1)], vec![(2, 4)], vec![(0, 4)]]);
+    assert_eq!(
+        sorted(step.eliminations),
+        [(0, 3, 3), (0, 5, 3), (2, 0, 3), (2, 1, 3), (2, 2, 3)]
+    );
+}
+
+#[test]
+fn death_blossom() {
+    // Stem (1,5) {2,5}: as 2 it locks the row 5 set (5,1) {1,3}, (5,5) {1,2}
+    // on 1 and 3, as 5 it leaves (1,6) {3,5} with 3. Either way 3 is in one
+    // of the petals.
+    let marks = Marks::new()
+        .cell((4, 4), "12")
+        .cell((4, 0), "13")
+        .cell((0, 4), "25")
+        .cell((0, 5), "35");
+
+    let step = step(DeathBlossom, &marks);
+    let Detail::Als { stem, sets, .. } = &step.detail else {
+        panic!("expected sets, got {:?}", step.detail);
+    };
+    assert_eq!(*stem, Some((0, 4)));
+    assert_eq!(sets, &[vec![(4, 0), (4, 4)], vec![(0, 5)]]);
+    assert_eq!(sorted(step.eliminations), [(0, 0, 3), (4, 5, 3)]);
+}
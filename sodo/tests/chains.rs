@@ -0,0 +1,54 @@
+mod common;
+
+use common::{Marks, except, row, sorted, step};
+use sodo::Detail;
+use sodo::strategy::{XChain, XYChain};
+
+#[test]
+fn x_chain_of_three_strong_links() {
+    // Rows 1, 4 and 8 each hold 6 in two cells, joined along columns 6 and 9.
+    // One of the ends, (1,1) or (8,3), holds 6.
+    let marks = Marks::new()
+        .without(6, &except(row(0), &[(0, 0), (0, 5)]))
+        .without(6, &except(row(3), &[(3, 5), (3, 8)]))
+        .without(6, &except(row(7), &[(7, 8), (7, 2)]));
+
+    let step = step(XChain::new(), &marks);
+    let Detail::Chain(chain) = &step.detail else {
+        panic!("expected a chain, got {:?}", step.detail);
+    };
+    assert_eq!(chain.len(), 5);
+    assert_eq!(
+        sorted(step.eliminations),
+        [(1, 2, 6), (2, 2, 6), (6, 0, 6), (8, 0, 6)]
+    );
+}
+
+#[test]
+fn xy_chain_through_bivalue_cells() {
+    // {1,2} {2,3} {3,4} {4,1}: whichever digit the first cell holds, one of
+    // the ends is 1.
+    let marks = Marks::new()
+        .cell((0, 0), "12")
+        .cell((0, 4), "23")
+        .cell((4, 4), "34")
+        .cell((4, 8), "14");
+
+    let step = step(XYChain::new(), &marks);
+    let Detail::Chain(chain) = &step.detail else {
+        panic!("expected a chain, got {:?}", step.detail);
+    };
+    assert_eq!(chain.len(), 7);
+    assert_eq!(sorted(step.eliminations), [(0, 8, 1), (4, 0, 1)]);
+}
+
+#[test]
+fn chains_respect_their_length_limit() {
+    let marks = Marks::new()
+        .cell((0, 0), "12")
+        .cell((0, 4), "23")
+        .cell((4, 4), "34")
+        .cell((4, 8), "14");
+
+    assert!(common::find(XYChain::new().max_length(5), &marks).is_none());
+}
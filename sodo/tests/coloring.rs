@@ -0,0 +1,55 @@
+mod common;
+
+use common::{Marks, boxed, col, except, row, sorted, step};
+use sodo::Detail;
+use sodo::strategy::{MultiColoring, SimpleColoring};
+
+#[test]
+fn color_trap() {
+    // One cluster of 3: (1,1) and (3,6) take one color, (1,5) and (8,6) the
+    // other. (8,1) sees both colors.
+    let marks = Marks::new()
+        .without(3, &except(row(0), &[(0, 0), (0, 4)]))
+        .without(3, &except(boxed(1), &[(0, 4), (2, 5)]))
+        .without(3, &except(col(5), &[(2, 5), (7, 5)]));
+
+    let step = step(SimpleColoring, &marks);
+    assert_eq!(step.strategy, "Simple Coloring");
+    assert_eq!(step.eliminations, [(7, 0, 3)]);
+}
+
+#[test]
+fn color_wrap() {
+    // Alternating around rows 1 and 5 and columns 2 and 5, (1,1) and (2,2)
+    // get the same color but share a box, so that color is false.
+    let marks = Marks::new()
+        .without(3, &except(row(0), &[(0, 0), (0, 4)]))
+        .without(3, &except(col(4), &[(0, 4), (4, 4)]))
+        .without(3, &except(row(4), &[(4, 4), (4, 1)]))
+        .without(3, &except(col(1), &[(4, 1), (1, 1)]));
+
+    let step = step(SimpleColoring, &marks);
+    assert_eq!(step.strategy, "Simple Coloring");
+    let Detail::Coloring(clusters) = &step.detail else {
+        panic!("expected a coloring, got {:?}", step.detail);
+    };
+    assert_eq!(clusters.len(), 1);
+    assert_eq!(sorted(step.eliminations), [(0, 0, 3), (1, 1, 3), (4, 4, 3)]);
+}
+
+#[test]
+fn multi_coloring() {
+    // Clusters on row 1 and column 3 touch in box 1: (1,1) and (2,3) can't
+    // both hold 7, so (1,7) or (8,3) does.
+    let marks = Marks::new()
+        .without(7, &except(row(0), &[(0, 0), (0, 6)]))
+        .without(7, &except(col(2), &[(1, 2), (7, 2)]));
+
+    assert!(common::find(SimpleColoring, &marks).is_none());
+    let step = step(MultiColoring, &marks);
+    let Detail::Coloring(clusters) = &step.detail else {
+        panic!("expected a coloring, got {:?}", step.detail);
+    };
+    assert_eq!(clusters.len(), 2);
+    assert_eq!(step.eliminations, [(7, 6, 7)]);
+}
@@ -0,0 +1,108 @@
+//! Candidate grids for strategy tests. Every cell of a fresh grid holds all
+//! nine candidates, which no strategy can use, so a test only narrows down
+//! the cells of the pattern it checks. Cells are zero-based (row, col) as in
+//! [`Step`]; comments count units from one, as steps display them.
+
+#![allow(dead_code)]
+
+use sodo::{Step, Strategy, Sudoku};
+
+pub type Cell = (usize, usize);
+
+pub struct Marks(Sudoku);
+
+impl Marks {
+    pub fn new() -> Self {
+        Self(Sudoku::new(9))
+    }
+
+    /// Keeps only `digits`, such as `"47"`, in each of `cells`.
+    pub fn cells(mut self, cells: &[Cell], digits: &str) -> Self {
+        for &(r, c) in cells {
+            for val in 1..=9 {
+                if !digits.contains((b'0' + val) as char) {
+                    self.0.eliminate(r, c, val);
+                }
+            }
+        }
+        self
+    }
+
+    /// Keeps only `digits` in the cell at (row, col).
+    pub fn cell(self, cell: Cell, digits: &str) -> Self {
+        self.cells(&[cell], digits)
+    }
+
+    /// Removes `val` from each of `cells`.
+    pub fn without(mut self, val: u8, cells: &[Cell]) -> Self {
+        for &(r, c) in cells {
+            self.0.eliminate(r, c, val);
+        }
+        self
+    }
+
+    /// Removes `val` from every cell of the grid except `cells`.
+    pub fn only(self, val: u8, cells: &[Cell]) -> Self {
+        let others: Vec<_> = all_cells().filter(|cell| !cells.contains(cell)).collect();
+        self.without(val, &others)
+    }
+
+    /// Places `val` at (row, col).
+    pub fn place(mut self, (r, c): Cell, val: u8) -> Self {
+        self.0.set(r, c, val).unwrap();
+        self
+    }
+
+    pub fn sudoku(&self) -> &Sudoku {
+        &self.0
+    }
+}
+
+pub fn all_cells() -> impl Iterator<Item = Cell> {
+    (0..9).flat_map(|r| (0..9).map(move |c| (r, c)))
+}
+
+pub fn row(r: usize) -> Vec<Cell> {
+    (0..9).map(|c| (r, c)).collect()
+}
+
+pub fn col(c: usize) -> Vec<Cell> {
+    (0..9).map(|r| (r, c)).collect()
+}
+
+pub fn boxed(b: usize) -> Vec<Cell> {
+    (0..9)
+        .map(|i| (b / 3 * 3 + i / 3, b % 3 * 3 + i % 3))
+        .collect()
+}
+
+/// Returns `cells` without `skip`.
+pub fn except(cells: Vec<Cell>, skip: &[Cell]) -> Vec<Cell> {
+    cells
+        .into_iter()
+        .filter(|cell| !skip.contains(cell))
+        .collect()
+}
+
+/// Returns the step `strategy` finds in `marks`.
+pub fn step(strategy: impl Strategy, marks: &Marks) -> Step {
+    let name = strategy.name();
+    find(strategy, marks).unwrap_or_else(|| panic!("{name} found no step"))
+}
+
+/// Returns the step `strategy` finds in `marks`, if any.
+pub fn find(strategy: impl Strategy, marks: &Marks) -> Option<Step> {
+    strategy.find_step(marks.sudoku())
+}
+
+/// Lists `val` in each of `cells`, as a step lists its eliminations.
+pub fn each(val: u8, cells: &[Cell]) -> Vec<(usize, usize, u8)> {
+    cells.iter().map(|&(r, c)| (r, c, val)).collect()
+}
+
+/// Sorts (row, col, value) triples so they compare regardless of the order
+/// a strategy found them in.
+pub fn sorted(mut v: Vec<(usize, usize, u8)>) -> Vec<(usize, usize, u8)> {
+    v.sort_unstable();
+    v
+}
@@ -0,0 +1,53 @@
+use sodo::{Solver, Sudoku};
+
+const EASY: &str =
+    "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+const MEDIUM: &str =
+    "..96.8.4.......6...2..1...589.4..2..4.......3..2..1.945...9..7...8.......3.7.41..";
+
+#[test]
+fn hint_gives_the_first_placement() {
+    let solver = Solver::new();
+    let easy = Sudoku::from_string(EASY, 9).unwrap();
+    assert_eq!(solver.hint(&easy), Some((0, 5, 8)));
+
+    let medium = Sudoku::from_string(MEDIUM, 9).unwrap();
+    assert_eq!(solver.hint(&medium), Some((1, 1, 8)));
+}
+
+#[test]
+fn explain_describes_each_step() {
+    let steps = Solver::new().explain(&Sudoku::from_string(EASY, 9).unwrap());
+    assert_eq!(steps.len(), 51);
+
+    let lines: Vec<_> = steps.iter().take(2).map(ToString::to_string).collect();
+    assert_eq!(
+        lines,
+        [
+            "Hidden Single: 8 in box 2 must go at r1c6",
+            "Hidden Single: 6 in box 2 must go at r1c4",
+        ]
+    );
+}
+
+#[test]
+fn explain_includes_eliminations_and_solves() {
+    let puzzle = Sudoku::from_string(MEDIUM, 9).unwrap();
+    let steps = Solver::new().explain(&puzzle);
+
+    let eliminations: Vec<_> = steps
+        .iter()
+        .filter(|step| !step.is_placement())
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        eliminations,
+        ["Pointing: 8 in box 6, column 7 (r5c7 r6c7) => r7c7<>8"]
+    );
+
+    let mut sudoku = puzzle;
+    for step in &steps {
+        assert!(step.apply(&mut sudoku), "{step} made no progress");
+    }
+    assert!(sudoku.is_solved());
+}
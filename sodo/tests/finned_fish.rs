@@ -0,0 +1,74 @@
+mod common;
+
+use common::{Marks, col, except, row, sorted, step};
+use sodo::strategy::FinnedFish;
+use sodo::{Detail, Unit};
+
+#[test]
+fn finned_x_wing_only_clears_the_fin_box() {
+    // Rows 1 and 5 hold 4 in columns 2 and 8; row 1 also has a fin in column 9.
+    let marks = Marks::new()
+        .without(4, &except(row(0), &[(0, 1), (0, 7), (0, 8)]))
+        .without(4, &except(row(4), &[(4, 1), (4, 7)]));
+
+    let step = step(FinnedFish::new(2), &marks);
+    assert_eq!(step.strategy, "Finned X-Wing");
+    assert_eq!(
+        step.units,
+        [Unit::Row(0), Unit::Row(4), Unit::Col(1), Unit::Col(7)]
+    );
+    assert_eq!(step.cells, [(0, 1), (0, 7), (4, 1), (4, 7)]);
+    assert_eq!(step.detail, Detail::Fins(vec![(0, 8)]));
+    assert_eq!(sorted(step.eliminations), [(1, 7, 4), (2, 7, 4)]);
+}
+
+#[test]
+fn sashimi_x_wing_is_missing_a_corner() {
+    // Row 1 holds 4 in column 2 and, instead of column 8, in fin column 9.
+    let marks = Marks::new()
+        .without(4, &except(row(0), &[(0, 1), (0, 8)]))
+        .without(4, &except(row(4), &[(4, 1), (4, 7)]));
+
+    let step = step(FinnedFish::new(2), &marks);
+    assert_eq!(step.strategy, "Sashimi X-Wing");
+    assert_eq!(step.cells, [(0, 1), (4, 1), (4, 7)]);
+    assert_eq!(step.detail, Detail::Fins(vec![(0, 8)]));
+    assert_eq!(sorted(step.eliminations), [(1, 7, 4), (2, 7, 4)]);
+}
+
+#[test]
+fn finned_swordfish_on_columns() {
+    // Columns 2, 5 and 8 hold 3 in rows 1, 5 and 9; column 8 also has a fin
+    // in row 8.
+    let cells = [(0, 1), (4, 1), (4, 4), (8, 4), (0, 7), (7, 7), (8, 7)];
+    let mut marks = Marks::new();
+    for c in [1, 4, 7] {
+        marks = marks.without(3, &except(col(c), &cells));
+    }
+
+    let step = step(FinnedFish::new(3), &marks);
+    assert_eq!(step.strategy, "Finned Swordfish");
+    assert_eq!(
+        step.units,
+        [
+            Unit::Col(1),
+            Unit::Col(4),
+            Unit::Col(7),
+            Unit::Row(0),
+            Unit::Row(4),
+            Unit::Row(8)
+        ]
+    );
+    assert_eq!(step.detail, Detail::Fins(vec![(7, 7)]));
+    assert_eq!(sorted(step.eliminations), [(8, 6, 3), (8, 8, 3)]);
+}
+
+#[test]
+fn fins_in_two_boxes_prove_nothing() {
+    // Row 1 has fins in columns 5 and 9, in different boxes.
+    let marks = Marks::new()
+        .without(4, &except(row(0), &[(0, 1), (0, 4), (0, 7), (0, 8)]))
+        .without(4, &except(row(4), &[(4, 1), (4, 7)]));
+
+    assert!(common::find(FinnedFish::new(2), &marks).is_none());
+}
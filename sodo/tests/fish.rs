@@ -0,0 +1,88 @@
+mod common;
+
+use common::{Marks, col, each, except, row, sorted, step};
+use sodo::Unit;
+use sodo::strategy::Fish;
+
+#[test]
+fn x_wing_on_rows() {
+    // In rows 2 and 6, 2 only fits in columns 3 and 8.
+    let corners = [(1, 2), (1, 7), (5, 2), (5, 7)];
+    let marks = Marks::new()
+        .without(2, &except(row(1), &corners))
+        .without(2, &except(row(5), &corners));
+
+    let step = step(Fish::new(2), &marks);
+    assert_eq!(
+        step.units,
+        [Unit::Row(1), Unit::Row(5), Unit::Col(2), Unit::Col(7)]
+    );
+    assert_eq!(step.cells, corners);
+    let mut cover = except(col(2), &corners);
+    cover.extend(except(col(7), &corners));
+    assert_eq!(sorted(step.eliminations), sorted(each(2, &cover)));
+}
+
+#[test]
+fn swordfish_on_columns() {
+    // In columns 1, 5 and 9, 6 only fits in rows 2, 5 and 8, two per column.
+    let cells = [(1, 0), (4, 0), (4, 4), (7, 4), (1, 8), (7, 8)];
+    let marks = Marks::new()
+        .without(6, &except(col(0), &cells))
+        .without(6, &except(col(4), &cells))
+        .without(6, &except(col(8), &cells));
+
+    let step = step(Fish::new(3), &marks);
+    assert_eq!(
+        step.units,
+        [
+            Unit::Col(0),
+            Unit::Col(4),
+            Unit::Col(8),
+            Unit::Row(1),
+            Unit::Row(4),
+            Unit::Row(7)
+        ]
+    );
+    let cover: Vec<_> = [1, 4, 7]
+        .into_iter()
+        .flat_map(|r| except(row(r), &[(r, 0), (r, 4), (r, 8)]))
+        .collect();
+    assert_eq!(sorted(step.eliminations), sorted(each(6, &cover)));
+}
+
+#[test]
+fn jellyfish_on_rows() {
+    // In rows 1, 3, 7 and 9, 9 only fits in columns 2, 4, 6 and 9.
+    let cells = [
+        (0, 1),
+        (0, 3),
+        (2, 3),
+        (2, 5),
+        (6, 5),
+        (6, 8),
+        (8, 1),
+        (8, 8),
+    ];
+    let mut marks = Marks::new();
+    for r in [0, 2, 6, 8] {
+        marks = marks.without(9, &except(row(r), &cells));
+    }
+
+    let step = step(Fish::new(4), &marks);
+    let cover: Vec<_> = [1, 3, 5, 8]
+        .into_iter()
+        .flat_map(|c| except(col(c), &[(0, c), (2, c), (6, c), (8, c)]))
+        .collect();
+    assert_eq!(sorted(step.eliminations), sorted(each(9, &cover)));
+}
+
+#[test]
+fn no_fish_without_matching_columns() {
+    // Rows 2 and 6 hold 2 in columns 3 and 8, and 3 and 9.
+    let marks = Marks::new()
+        .without(2, &except(row(1), &[(1, 2), (1, 7)]))
+        .without(2, &except(row(5), &[(5, 2), (5, 8)]));
+
+    assert!(common::find(Fish::new(2), &marks).is_none());
+}
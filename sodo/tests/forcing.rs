@@ -0,0 +1,65 @@
+mod common;
+
+use common::{Marks, except, row, step};
+use sodo::strategy::{CellForcingChain, Nishio, UnitForcingChain};
+use sodo::{Detail, Step, Unit};
+
+fn branches(step: &Step) -> Vec<String> {
+    match &step.detail {
+        Detail::Forcing(branches) => branches.iter().map(ToString::to_string).collect(),
+        other => panic!("expected forcing branches, got {other:?}"),
+    }
+}
+
+#[test]
+fn nishio() {
+    // Rows 3 and 6 hold 7 in two cells each. A 7 at (1,1) leaves row 3 with
+    // (3,7) alone, which empties row 6.
+    let marks = Marks::new()
+        .without(7, &except(row(2), &[(2, 1), (2, 6)]))
+        .without(7, &except(row(5), &[(5, 0), (5, 6)]));
+
+    let step = step(Nishio::new(), &marks);
+    assert_eq!(step.eliminations, [(0, 0, 7)]);
+    assert_eq!(branches(&step), ["r1c1=7 -> r3c7=7 -> contradiction"]);
+}
+
+#[test]
+fn cell_forcing_chain() {
+    // (1,1) {1,2}: either way a 3 lands in row 5 or column 5, at (1,5) or
+    // (5,1), and (5,5) sees both.
+    let marks = Marks::new()
+        .cell((0, 0), "12")
+        .cell((0, 4), "13")
+        .cell((4, 0), "23");
+
+    let step = step(CellForcingChain::new(), &marks);
+    assert_eq!(step.cells, [(0, 0)]);
+    assert_eq!(step.eliminations, [(4, 4, 3)]);
+    assert_eq!(branches(&step), ["r1c1=1 -> r1c5=3", "r1c1=2 -> r5c1=3"]);
+}
+
+#[test]
+fn unit_forcing_chain() {
+    // In row 1, 5 only fits at (1,1) or (1,5). From (1,5) it turns (5,5)
+    // {5,6} into 6 and (5,1) {5,6} into 5, so either way column 1 has a 5
+    // that (2,1) sees.
+    let marks = Marks::new()
+        .without(5, &except(row(0), &[(0, 0), (0, 4)]))
+        .cells(&[(4, 0), (4, 4)], "56");
+
+    let step = step(UnitForcingChain::new(), &marks);
+    assert_eq!(step.units, [Unit::Row(0)]);
+    assert_eq!(step.cells, [(0, 0), (0, 4)]);
+    assert_eq!(step.eliminations, [(1, 0, 5)]);
+    assert_eq!(branches(&step), ["r1c1=5", "r1c5=5 -> r5c5=6 -> r5c1=5"]);
+}
+
+#[test]
+fn forcing_depth_limits_the_search() {
+    let marks = Marks::new()
+        .without(7, &except(row(2), &[(2, 1), (2, 6)]))
+        .without(7, &except(row(5), &[(5, 0), (5, 6)]));
+
+    assert!(common::find(Nishio::new().max_depth(0), &marks).is_none());
+}
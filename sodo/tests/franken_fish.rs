@@ -0,0 +1,80 @@
+mod common;
+
+use common::{Marks, boxed, col, except, row, sorted, step};
+use sodo::strategy::{FrankenFish, MutantFish};
+use sodo::{Detail, Unit};
+
+#[test]
+fn franken_x_wing_on_a_row_and_a_box() {
+    // Row 1 holds 5 in columns 4 and 6, and box 5 holds it in r4c4 and r6c6,
+    // so columns 4 and 6 cover both.
+    let marks = Marks::new()
+        .without(5, &except(row(0), &[(0, 3), (0, 5)]))
+        .without(5, &except(boxed(4), &[(3, 3), (5, 5)]));
+
+    let step = step(FrankenFish::new(2), &marks);
+    assert_eq!(step.strategy, "Franken X-Wing");
+    assert_eq!(
+        step.units,
+        [Unit::Row(0), Unit::Box(4), Unit::Col(3), Unit::Col(5)]
+    );
+    assert_eq!(step.cells, [(0, 3), (0, 5), (3, 3), (5, 5)]);
+    assert_eq!(step.detail, Detail::None);
+    let rows = [1, 2, 6, 7, 8];
+    let expected: Vec<_> = rows.iter().flat_map(|&r| [(r, 3, 5), (r, 5, 5)]).collect();
+    assert_eq!(sorted(step.eliminations), expected);
+}
+
+#[test]
+fn mutant_x_wing_on_a_row_and_a_column() {
+    // Row 1 holds 5 only in box 2, column 5 holds it in rows 2 and 8, so box
+    // 2 and row 8 cover both. Column 4 holds it only in row 1, which keeps
+    // finned fish through it from proving anything.
+    let marks = Marks::new()
+        .without(5, &except(row(0), &[(0, 3), (0, 5)]))
+        .without(5, &except(col(4), &[(1, 4), (7, 4)]))
+        .without(5, &except(col(3), &[(0, 3)]));
+
+    let step = step(MutantFish::new(2), &marks);
+    assert_eq!(step.strategy, "Mutant X-Wing");
+    assert_eq!(
+        step.units,
+        [Unit::Row(0), Unit::Col(4), Unit::Box(1), Unit::Row(7)]
+    );
+    assert_eq!(step.cells, [(0, 3), (0, 5), (1, 4), (7, 4)]);
+    assert_eq!(
+        sorted(step.eliminations),
+        [
+            (1, 5, 5),
+            (2, 5, 5),
+            (7, 0, 5),
+            (7, 1, 5),
+            (7, 2, 5),
+            (7, 5, 5),
+            (7, 6, 5),
+            (7, 7, 5),
+            (7, 8, 5)
+        ]
+    );
+}
+
+#[test]
+fn finned_mutant_x_wing() {
+    // As above, but column 4 keeps 5 outside box 8: column 4 and row 8 then
+    // cover r1c4 and r8c5, leaving fins r1c6 and r2c5 in box 2, and r2c4 and
+    // r3c4 see them both.
+    let marks = Marks::new()
+        .without(5, &except(row(0), &[(0, 3), (0, 5)]))
+        .without(5, &except(col(4), &[(1, 4), (7, 4)]))
+        .without(5, &[(6, 3), (7, 3), (8, 3)]);
+
+    let step = step(MutantFish::new(2), &marks);
+    assert_eq!(step.strategy, "Finned Mutant X-Wing");
+    assert_eq!(
+        step.units,
+        [Unit::Row(0), Unit::Col(4), Unit::Col(3), Unit::Row(7)]
+    );
+    assert_eq!(step.cells, [(0, 3), (7, 4)]);
+    assert_eq!(step.detail, Detail::Fins(vec![(0, 5), (1, 4)]));
+    assert_eq!(sorted(step.eliminations), [(1, 3, 5), (2, 3, 5)]);
+}
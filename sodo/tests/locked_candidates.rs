@@ -0,0 +1,52 @@
+mod common;
+
+use common::{Marks, each, sorted, step};
+use sodo::Unit;
+use sodo::strategy::{Claiming, Pointing};
+
+#[test]
+fn pointing_clears_the_rest_of_the_row() {
+    // Within box 1, 5 is confined to row 1.
+    let marks = Marks::new().without(5, &[(0, 1), (1, 0), (1, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+
+    let step = step(Pointing, &marks);
+    assert_eq!(step.units, [Unit::Box(0), Unit::Row(0)]);
+    assert_eq!(step.cells, [(0, 0), (0, 2)]);
+    assert_eq!(
+        sorted(step.eliminations),
+        each(5, &[(0, 3), (0, 4), (0, 5), (0, 6), (0, 7), (0, 8)])
+    );
+}
+
+#[test]
+fn pointing_clears_the_rest_of_the_column() {
+    // Within box 9, 3 is confined to column 9.
+    let marks = Marks::new().without(3, &[(6, 6), (6, 7), (7, 6), (7, 7), (8, 6), (8, 7), (8, 8)]);
+
+    let step = step(Pointing, &marks);
+    assert_eq!(step.units, [Unit::Box(8), Unit::Col(8)]);
+    assert_eq!(
+        sorted(step.eliminations),
+        each(3, &[(0, 8), (1, 8), (2, 8), (3, 8), (4, 8), (5, 8)])
+    );
+}
+
+#[test]
+fn claiming_clears_the_rest_of_the_box() {
+    // Within row 5, 7 is confined to box 5.
+    let marks = Marks::new().without(7, &[(4, 0), (4, 1), (4, 2), (4, 4), (4, 6), (4, 7), (4, 8)]);
+
+    let step = step(Claiming, &marks);
+    assert_eq!(step.units, [Unit::Row(4), Unit::Box(4)]);
+    assert_eq!(step.cells, [(4, 3), (4, 5)]);
+    assert_eq!(
+        sorted(step.eliminations),
+        each(7, &[(3, 3), (3, 4), (3, 5), (5, 3), (5, 4), (5, 5)])
+    );
+}
+
+#[test]
+fn no_locked_candidates_in_an_open_grid() {
+    assert!(common::find(Pointing, &Marks::new()).is_none());
+    assert!(common::find(Claiming, &Marks::new()).is_none());
+}
@@ -0,0 +1,39 @@
+use sodo::strategy::{HiddenSingles, NakedSingles, XYZWing};
+use sodo::{Solver, Strategy, Sudoku};
+
+/// Needs an XYZ-Wing.
+const HARD: &str =
+    "...249.1.8.....9....1..7.5........4.75.6.1.89.1........6.9..7....5.....3.3.852...";
+
+#[test]
+fn restarts_from_the_easiest_strategy_after_each_step() {
+    let mut sudoku = Sudoku::from_string(HARD, 9).unwrap();
+    let steps = Solver::new().explain(&sudoku);
+    assert!(steps.iter().any(|step| step.strategy == "XYZ-Wing"));
+
+    for step in &steps {
+        if HiddenSingles.find_step(&sudoku).is_some() {
+            assert_eq!(step.strategy, "Hidden Single");
+        } else if NakedSingles.find_step(&sudoku).is_some() {
+            assert_eq!(step.strategy, "Naked Single");
+        }
+        step.apply(&mut sudoku);
+    }
+    assert!(sudoku.is_solved());
+}
+
+#[test]
+fn custom_strategies_are_tried_easiest_first() {
+    // Listed hardest first, the singles must still win whenever they apply.
+    let strategies: Vec<Box<dyn Strategy>> = vec![
+        Box::new(XYZWing),
+        Box::new(NakedSingles),
+        Box::new(HiddenSingles),
+    ];
+    let solver = Solver::with_strategies(strategies);
+    let sudoku = Sudoku::from_string(HARD, 9).unwrap();
+
+    let first = solver.next_step(&sudoku).unwrap();
+    assert_eq!(first.strategy, "Hidden Single");
+    assert_eq!(first.placements, [(0, 0, 5)]);
+}
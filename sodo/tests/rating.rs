@@ -0,0 +1,83 @@
+use sodo::{Difficulty, Solver, Sudoku, strategy};
+
+const EASY: &str =
+    "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+/// Needs Pointing.
+const MEDIUM: &str =
+    "..96.8.4.......6...2..1...589.4..2..4.......3..2..1.945...9..7...8.......3.7.41..";
+/// Needs an XYZ-Wing.
+const HARD: &str =
+    "...249.1.8.....9....1..7.5........4.75.6.1.89.1........6.9..7....5.....3.3.852...";
+/// Needs a Franken Swordfish and a grouped AIC.
+const EXPERT: &str =
+    "..97.35.....6...7..8....2.3.7...6...15.....64...2...5.8.3....1..6...9.....73.26..";
+
+fn rate(solver: &Solver, puzzle: &str) -> String {
+    let sudoku = Sudoku::from_string(puzzle, 9).unwrap();
+    solver.rate(&sudoku).unwrap().to_string()
+}
+
+#[test]
+fn rates_known_puzzles() {
+    let solver = Solver::new();
+    assert_eq!(rate(&solver, EASY), "ER 1.2/EP 1.2/ED 1.2");
+    assert_eq!(rate(&solver, HARD), "ER 4.4/EP 1.2/ED 1.2");
+    assert_eq!(rate(&solver, EXPERT), "ER 7.0/EP 1.2/ED 1.2");
+}
+
+#[test]
+fn ep_and_ed_follow_the_first_steps() {
+    // Without hidden singles, the expert puzzle opens with a Pointing (2.6)
+    // and first places a value after a Hidden Pair (3.4).
+    let ids: Vec<_> = strategy::registry()
+        .iter()
+        .map(|info| info.id)
+        .filter(|&id| id != "hidden-single")
+        .collect();
+    let solver = Solver::from_ids(&ids).unwrap();
+    assert_eq!(rate(&solver, EXPERT), "ER 7.0/EP 2.6/ED 3.4");
+}
+
+#[test]
+fn unrated_when_the_strategies_get_stuck() {
+    let solver = Solver::from_ids(&["hidden-single", "naked-single"]).unwrap();
+    let sudoku = Sudoku::from_string(HARD, 9).unwrap();
+    assert_eq!(solver.rate(&sudoku), None);
+}
+
+fn difficulty(puzzle: &str) -> Difficulty {
+    let sudoku = Sudoku::from_string(puzzle, 9).unwrap();
+    Solver::new().rate(&sudoku).unwrap().difficulty()
+}
+
+/// Returns a solver limited to the strategies at or below `level`.
+fn up_to(level: Difficulty) -> Solver {
+    let ids: Vec<_> = strategy::registry()
+        .iter()
+        .filter(|info| info.level <= level)
+        .map(|info| info.id)
+        .collect();
+    Solver::from_ids(&ids).unwrap()
+}
+
+#[test]
+fn classifies_each_tier() {
+    assert_eq!(difficulty(EASY), Difficulty::Easy);
+    assert_eq!(difficulty(MEDIUM), Difficulty::Medium);
+    assert_eq!(difficulty(HARD), Difficulty::Hard);
+    assert_eq!(difficulty(EXPERT), Difficulty::Expert);
+}
+
+#[test]
+fn each_tier_needs_its_strategies() {
+    // Medium needs more than singles, Hard needs fish or wings, and Expert
+    // needs more than those.
+    for (puzzle, level) in [
+        (MEDIUM, Difficulty::Easy),
+        (HARD, Difficulty::Medium),
+        (EXPERT, Difficulty::Hard),
+    ] {
+        let sudoku = Sudoku::from_string(puzzle, 9).unwrap();
+        assert_eq!(up_to(level).rate(&sudoku), None, "{puzzle}");
+    }
+}
@@ -0,0 +1,105 @@
+use sodo::{Solver, Sudoku, strategy};
+use std::process::Command;
+
+const EASY: &str =
+    "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+/// Needs a Unique Rectangle once singles, locked candidates and pairs run out.
+const RECTANGLE: &str =
+    "2..834....5.2...47.........1...235....7...8....619...4.........78...2.6....349..2";
+const WITH_RECTANGLE: &str =
+    "hidden-single,naked-single,pointing,claiming,naked-pair,hidden-pair,unique-rectangle";
+
+fn sodo(args: &[&str]) -> (bool, String, String) {
+    let out = Command::new(env!("CARGO_BIN_EXE_sodo"))
+        .args(args)
+        .output()
+        .unwrap();
+    let text = |bytes| String::from_utf8(bytes).unwrap();
+    (out.status.success(), text(out.stdout), text(out.stderr))
+}
+
+#[test]
+fn ids_are_unique_and_ordered_by_difficulty() {
+    let registry = strategy::registry();
+    for (i, info) in registry.iter().enumerate() {
+        assert!(registry[..i].iter().all(|other| other.id != info.id));
+        assert_eq!(strategy::lookup(info.id).unwrap().name, info.name);
+        assert_eq!(info.build().name(), info.name);
+    }
+    assert!(
+        registry
+            .windows(2)
+            .all(|w| w[0].difficulty <= w[1].difficulty)
+    );
+    assert!(strategy::lookup("no-such-strategy").is_none());
+}
+
+#[test]
+fn by_ids_keeps_registry_order() {
+    let names: Vec<_> = strategy::by_ids(&["xy-wing", "naked-single", "x-wing"])
+        .unwrap()
+        .iter()
+        .map(|s| s.name())
+        .collect();
+    assert_eq!(names, ["Naked Single", "X-Wing", "XY-Wing"]);
+}
+
+#[test]
+fn from_ids_rejects_unknown_ids() {
+    let err = Solver::from_ids(&["naked-single", "x-wings"])
+        .err()
+        .unwrap();
+    assert_eq!(err, "Unknown strategy: x-wings");
+}
+
+#[test]
+fn from_ids_only_uses_the_named_strategies() {
+    let puzzle = Sudoku::from_string(EASY, 9).unwrap();
+    let steps = Solver::from_ids(&["naked-single"])
+        .unwrap()
+        .explain(&puzzle);
+    assert!(!steps.is_empty());
+    assert!(steps.iter().all(|step| step.strategy == "Naked Single"));
+}
+
+#[test]
+fn cli_rejects_unknown_ids() {
+    let (ok, _, err) = sodo(&["explain", EASY, "--exclude", "x-wings"]);
+    assert!(!ok);
+    assert!(err.starts_with("Unknown strategy: x-wings\nAvailable: hidden-single, "));
+}
+
+#[test]
+fn cli_excludes_strategies() {
+    let (ok, out, _) = sodo(&["explain", EASY, "--exclude", "hidden-single"]);
+    assert!(ok);
+    assert!(out.contains("Naked Single"));
+    assert!(!out.contains("Hidden Single"));
+}
+
+#[test]
+fn cli_naming_a_uniqueness_strategy_enables_it() {
+    let (ok, out, _) = sodo(&["explain", RECTANGLE, "--strategies", WITH_RECTANGLE]);
+    assert!(ok);
+    assert!(out.contains("Unique Rectangle"), "{out}");
+
+    let without = WITH_RECTANGLE.trim_end_matches(",unique-rectangle");
+    let (_, out, _) = sodo(&["explain", RECTANGLE, "--strategies", without]);
+    assert!(!out.contains("Unique Rectangle"));
+}
+
+#[test]
+fn stats_count_steps_by_registered_name() {
+    let puzzle = Sudoku::from_string(RECTANGLE, 9).unwrap();
+    let ids: Vec<_> = WITH_RECTANGLE.split(',').collect();
+    let mut solver = Solver::from_ids(&ids).unwrap().use_uniqueness(true);
+    let (_, stats) = solver.solve_with_stats(puzzle).unwrap();
+
+    assert!(stats.strategies_used.contains_key("Unique Rectangle"));
+    for name in stats.strategies_used.keys() {
+        assert!(
+            strategy::registry().iter().any(|info| info.name == name),
+            "{name} is not registered"
+        );
+    }
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "serde")]
+
+use sodo::Sudoku;
+
+const PUZZLE: &str =
+    "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+#[test]
+fn round_trip() {
+    let sudoku = Sudoku::from_string(PUZZLE, 9).unwrap();
+    let json = serde_json::to_string(&sudoku).unwrap();
+    let back: Sudoku = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.to_string_compact(), sudoku.to_string_compact());
+    assert_eq!(back.candidates(0, 2), sudoku.candidates(0, 2));
+}
+
+#[test]
+fn rejects_malformed_layout() {
+    let json = serde_json::to_value(Sudoku::from_string(PUZZLE, 9).unwrap()).unwrap();
+
+    let mut bad_size = json.clone();
+    bad_size["size"] = 5.into();
+    assert!(serde_json::from_value::<Sudoku>(bad_size).is_err());
+
+    let mut short_grid = json.clone();
+    short_grid["grid"].as_array_mut().unwrap().pop();
+    assert!(serde_json::from_value::<Sudoku>(short_grid).is_err());
+
+    let mut bad_value = json;
+    bad_value["grid"][0][0] = serde_json::json!({ "Given": 10 });
+    assert!(serde_json::from_value::<Sudoku>(bad_value).is_err());
+}
+
+#[test]
+fn keeps_eliminated_marks() {
+    let mut sudoku = Sudoku::from_string(PUZZLE, 9).unwrap();
+    sudoku.eliminate(0, 2, 2);
+    let json = serde_json::to_string(&sudoku).unwrap();
+    let back: Sudoku = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.candidates(0, 2).iter().collect::<Vec<_>>(), [1, 4]);
+}
+
+#[test]
+fn rejects_marks_beyond_size() {
+    let json = serde_json::to_value(Sudoku::from_string(PUZZLE, 9).unwrap()).unwrap();
+
+    let mut digit_64 = json.clone();
+    digit_64["marks"][2] = (1u64 << 63 | 0b1011).into();
+    assert!(serde_json::from_value::<Sudoku>(digit_64).is_err());
+
+    let mut short_marks = json.clone();
+    short_marks["marks"].as_array_mut().unwrap().pop();
+    assert!(serde_json::from_value::<Sudoku>(short_marks).is_err());
+
+    let mut no_marks = json;
+    no_marks.as_object_mut().unwrap().remove("marks");
+    let sudoku: Sudoku = serde_json::from_value(no_marks).unwrap();
+    assert_eq!(sudoku.candidates(0, 2).len(), 3);
+}
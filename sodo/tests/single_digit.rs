@@ -0,0 +1,62 @@
+mod common;
+
+use common::{Marks, boxed, col, except, row, sorted, step};
+use sodo::Unit;
+use sodo::strategy::{EmptyRectangle, Skyscraper, TurbotFish, TwoStringKite};
+
+#[test]
+fn skyscraper() {
+    // In rows 2 and 8, 1 only fits in column 1 and one more column. The
+    // column 1 ends can't both hold it, so one of the roofs, (2,5) or (8,4),
+    // does.
+    let marks = Marks::new()
+        .without(1, &except(row(1), &[(1, 0), (1, 4)]))
+        .without(1, &except(row(7), &[(7, 0), (7, 3)]));
+
+    let step = step(Skyscraper, &marks);
+    assert_eq!(step.units, [Unit::Row(1), Unit::Row(7)]);
+    assert_eq!(step.cells, [(1, 4), (1, 0), (7, 0), (7, 3)]);
+    assert_eq!(
+        sorted(step.eliminations),
+        [(0, 3, 1), (2, 3, 1), (6, 4, 1), (8, 4, 1)]
+    );
+}
+
+#[test]
+fn two_string_kite() {
+    // A row 1 link and a column 1 link meet in box 1, so one of their far
+    // ends, (1,7) or (8,1), holds 5.
+    let marks = Marks::new()
+        .without(5, &except(row(0), &[(0, 1), (0, 6)]))
+        .without(5, &except(col(0), &[(2, 0), (7, 0)]));
+
+    let step = step(TwoStringKite, &marks);
+    assert_eq!(step.units, [Unit::Row(0), Unit::Col(0)]);
+    assert_eq!(step.cells, [(0, 6), (0, 1), (2, 0), (7, 0)]);
+    assert_eq!(step.eliminations, [(7, 6, 5)]);
+}
+
+#[test]
+fn turbot_fish_through_a_box() {
+    // A box 1 link and a column 8 link, joined along row 3.
+    let marks = Marks::new()
+        .without(8, &except(boxed(0), &[(0, 1), (2, 2)]))
+        .without(8, &except(col(7), &[(2, 7), (6, 7)]));
+
+    let step = step(TurbotFish, &marks);
+    assert_eq!(step.cells, [(6, 7), (2, 7), (2, 2), (0, 1)]);
+    assert_eq!(step.eliminations, [(6, 1, 8)]);
+}
+
+#[test]
+fn empty_rectangle() {
+    // In box 1, 2 only fits in row 2 and column 2; column 7 links row 2 to
+    // row 8, so (8,2) can't hold it.
+    let marks = Marks::new()
+        .without(2, &[(0, 0), (0, 2), (2, 0), (2, 2)])
+        .without(2, &except(col(6), &[(1, 6), (7, 6)]));
+
+    let step = step(EmptyRectangle, &marks);
+    assert_eq!(step.units, [Unit::Box(0), Unit::Col(6)]);
+    assert_eq!(step.eliminations, [(7, 1, 2)]);
+}
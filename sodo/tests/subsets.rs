@@ -0,0 +1,122 @@
+mod common;
+
+use common::{Marks, boxed, col, row, sorted, step};
+use sodo::strategy::{HiddenSubset, NakedSubset};
+use sodo::{Candidates, Unit};
+
+/// Lists each of `digits` in each of `cells` that isn't in `skip`.
+fn from(
+    cells: Vec<(usize, usize)>,
+    skip: &[(usize, usize)],
+    digits: &[u8],
+) -> Vec<(usize, usize, u8)> {
+    let mut out: Vec<_> = cells
+        .into_iter()
+        .filter(|cell| !skip.contains(cell))
+        .flat_map(|(r, c)| digits.iter().map(move |&val| (r, c, val)))
+        .collect();
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+#[test]
+fn naked_pair_in_a_row() {
+    let pair = [(2, 1), (2, 7)];
+    let marks = Marks::new().cells(&pair, "48");
+
+    let step = step(NakedSubset::new(2), &marks);
+    assert_eq!(step.units, [Unit::Row(2)]);
+    assert_eq!(step.cells, pair);
+    assert_eq!(step.digits, [4, 8].into_iter().collect::<Candidates>());
+    assert_eq!(sorted(step.eliminations), from(row(2), &pair, &[4, 8]));
+}
+
+#[test]
+fn naked_pair_locks_every_unit_it_shares() {
+    let pair = [(0, 0), (0, 1)];
+    let marks = Marks::new().cells(&pair, "12");
+
+    let step = step(NakedSubset::new(2), &marks);
+    assert_eq!(step.units, [Unit::Row(0), Unit::Box(0)]);
+    let mut unit_cells = row(0);
+    unit_cells.extend(boxed(0));
+    assert_eq!(sorted(step.eliminations), from(unit_cells, &pair, &[1, 2]));
+}
+
+#[test]
+fn naked_triple_without_all_digits_in_each_cell() {
+    let triple = [(4, 0), (4, 4), (4, 8)];
+    let marks = Marks::new()
+        .cell(triple[0], "12")
+        .cell(triple[1], "23")
+        .cell(triple[2], "13");
+
+    let step = step(NakedSubset::new(3), &marks);
+    assert_eq!(step.units, [Unit::Row(4)]);
+    assert_eq!(step.digits, [1, 2, 3].into_iter().collect::<Candidates>());
+    assert_eq!(sorted(step.eliminations), from(row(4), &triple, &[1, 2, 3]));
+}
+
+#[test]
+fn naked_quad_in_a_box() {
+    let quad = [(3, 6), (4, 7), (5, 6), (5, 8)];
+    let marks = Marks::new()
+        .cell(quad[0], "15")
+        .cell(quad[1], "569")
+        .cell(quad[2], "19")
+        .cell(quad[3], "1569");
+
+    let step = step(NakedSubset::new(4), &marks);
+    assert_eq!(step.units, [Unit::Box(5)]);
+    assert_eq!(
+        sorted(step.eliminations),
+        from(boxed(5), &quad, &[1, 5, 6, 9])
+    );
+}
+
+#[test]
+fn hidden_pair_clears_the_other_candidates_of_its_cells() {
+    // In row 1, 6 and 9 only fit in columns 4 and 8.
+    let pair = [(0, 3), (0, 7)];
+    let others: Vec<_> = row(0)
+        .into_iter()
+        .filter(|cell| !pair.contains(cell))
+        .collect();
+    let marks = Marks::new().without(6, &others).without(9, &others);
+
+    let step = step(HiddenSubset::new(2), &marks);
+    assert_eq!(step.units, [Unit::Row(0)]);
+    assert_eq!(step.cells, pair);
+    assert_eq!(step.digits, [6, 9].into_iter().collect::<Candidates>());
+    assert_eq!(
+        sorted(step.eliminations),
+        from(pair.to_vec(), &[], &[1, 2, 3, 4, 5, 7, 8])
+    );
+}
+
+#[test]
+fn hidden_triple_with_a_digit_missing_from_each_cell() {
+    // In column 3, 1, 4 and 7 only fit in rows 1, 5 and 9, each in two of them.
+    let triple = [(0, 2), (4, 2), (8, 2)];
+    let others: Vec<_> = col(2)
+        .into_iter()
+        .filter(|cell| !triple.contains(cell))
+        .collect();
+    let marks = Marks::new()
+        .without(1, &others)
+        .without(4, &others)
+        .without(7, &others)
+        .without(7, &[(0, 2)])
+        .without(1, &[(4, 2)])
+        .without(4, &[(8, 2)]);
+
+    let step = step(HiddenSubset::new(3), &marks);
+    assert_eq!(step.units, [Unit::Col(2)]);
+    assert_eq!(step.cells, triple);
+    assert_eq!(step.digits, [1, 4, 7].into_iter().collect::<Candidates>());
+    assert_eq!(
+        sorted(step.eliminations),
+        from(triple.to_vec(), &[], &[2, 3, 5, 6, 8, 9])
+    );
+}
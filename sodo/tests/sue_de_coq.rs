@@ -0,0 +1,61 @@
+mod common;
+
+use common::{Marks, boxed, each, except, row, sorted, step};
+use sodo::strategy::SueDeCoq;
+use sodo::{Strategy, Sudoku, Unit};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn sue_de_coq() {
+    // (1,1) and (1,2) hold 1-4 between them. With (1,6) {1,2} in the row and
+    // (2,1) {3,4} in the box, 1 and 2 stay in row 1, and 3 and 4 in box 1.
+    let core = [(0, 0), (0, 1)];
+    let marks = Marks::new()
+        .cell(core[0], "123")
+        .cell(core[1], "234")
+        .cell((0, 5), "12")
+        .cell((1, 0), "34");
+
+    let step = step(SueDeCoq, &marks);
+    assert_eq!(step.units, [Unit::Box(0), Unit::Row(0)]);
+    assert_eq!(step.cells, [(0, 0), (0, 1), (0, 5), (1, 0)]);
+
+    let in_line = except(row(0), &[core[0], core[1], (0, 5)]);
+    let in_box = except(boxed(0), &[core[0], core[1], (1, 0)]);
+    let mut expected = each(1, &in_line);
+    expected.extend(each(2, &in_line));
+    expected.extend(each(3, &in_box));
+    expected.extend(each(4, &in_box));
+    assert_eq!(sorted(step.eliminations), sorted(expected));
+}
+
+#[test]
+fn no_sue_de_coq_when_the_groups_share_a_digit() {
+    let marks = Marks::new()
+        .cell((0, 0), "123")
+        .cell((0, 1), "234")
+        .cell((0, 5), "12")
+        .cell((1, 0), "23");
+
+    assert!(common::find(SueDeCoq, &marks).is_none());
+}
+
+#[test]
+fn sue_de_coq_stays_fast_on_16x16() {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut sudoku = Sudoku::new(16);
+        assert!(SueDeCoq.find_step(&sudoku).is_none());
+        for c in 0..16 {
+            sudoku.set(0, c, c as u8 + 1).unwrap();
+        }
+        tx.send(SueDeCoq.find_step(&sudoku)).unwrap();
+    });
+
+    let step = rx
+        .recv_timeout(Duration::from_secs(10))
+        .expect("search timed out");
+    assert!(step.is_none());
+}
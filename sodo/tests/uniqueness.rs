@@ -0,0 +1,128 @@
+mod common;
+
+use common::{Marks, all_cells, each, except, row, sorted, step};
+use sodo::Unit;
+use sodo::strategy::{BugPlusOne, UniqueRectangle};
+
+// Every rectangle here has corners (1,1), (1,5), (2,1) and (2,5), spanning
+// boxes 1 and 2, on digits 1 and 2.
+const FLOOR: [(usize, usize); 2] = [(0, 0), (0, 4)];
+const ROOF: [(usize, usize); 2] = [(1, 0), (1, 4)];
+const CORNERS: [(usize, usize); 4] = [(0, 0), (0, 4), (1, 0), (1, 4)];
+
+#[test]
+fn type_1() {
+    let marks = Marks::new().cells(&CORNERS[..3], "12").cell((1, 4), "1259");
+
+    let step = step(UniqueRectangle, &marks);
+    assert_eq!(step.strategy, "Unique Rectangle Type 1");
+    assert_eq!(step.cells, CORNERS);
+    assert_eq!(sorted(step.eliminations), [(1, 4, 1), (1, 4, 2)]);
+}
+
+#[test]
+fn type_2() {
+    // Both roof corners have the extra 5, so one of them holds it.
+    let marks = Marks::new().cells(&FLOOR, "12").cells(&ROOF, "125");
+
+    let step = step(UniqueRectangle, &marks);
+    assert_eq!(step.strategy, "Unique Rectangle Type 2");
+    assert_eq!(sorted(step.eliminations), each(5, &except(row(1), &ROOF)));
+}
+
+#[test]
+fn type_3() {
+    // The roof's extras 3 and 4 form a naked pair with (2,8).
+    let marks = Marks::new()
+        .cells(&FLOOR, "12")
+        .cell(ROOF[0], "123")
+        .cell(ROOF[1], "124")
+        .cell((1, 7), "34");
+
+    let step = step(UniqueRectangle, &marks);
+    assert_eq!(step.strategy, "Unique Rectangle Type 3");
+    assert_eq!(step.units, [Unit::Row(1)]);
+    let others = except(row(1), &[ROOF[0], ROOF[1], (1, 7)]);
+    let mut expected = each(3, &others);
+    expected.extend(each(4, &others));
+    assert_eq!(sorted(step.eliminations), sorted(expected));
+}
+
+#[test]
+fn type_4() {
+    // In row 2, 1 only fits in the roof, so neither roof corner can be 2.
+    let marks = Marks::new()
+        .cells(&FLOOR, "12")
+        .cell(ROOF[0], "125")
+        .cell(ROOF[1], "127")
+        .without(1, &except(row(1), &ROOF));
+
+    let step = step(UniqueRectangle, &marks);
+    assert_eq!(step.strategy, "Unique Rectangle Type 4");
+    assert_eq!(step.units, [Unit::Row(1)]);
+    assert_eq!(sorted(step.eliminations), [(1, 0, 2), (1, 4, 2)]);
+}
+
+#[test]
+fn type_5() {
+    // Diagonal corners share the extra 5; cells seeing both lose it.
+    let marks = Marks::new()
+        .cells(&[(0, 0), (1, 4)], "12")
+        .cells(&[(0, 4), (1, 0)], "125");
+
+    let step = step(UniqueRectangle, &marks);
+    assert_eq!(step.strategy, "Unique Rectangle Type 5");
+    assert_eq!(
+        sorted(step.eliminations),
+        [(0, 1, 5), (0, 2, 5), (1, 3, 5), (1, 5, 5)]
+    );
+}
+
+#[test]
+fn type_6() {
+    // In rows 1 and 2, 1 only fits in the rectangle, so it takes the
+    // bivalue diagonal.
+    let marks = Marks::new()
+        .cells(&[(0, 0), (1, 4)], "12")
+        .cell((0, 4), "125")
+        .cell((1, 0), "126")
+        .without(1, &except(row(0), &CORNERS))
+        .without(1, &except(row(1), &CORNERS));
+
+    let step = step(UniqueRectangle, &marks);
+    assert_eq!(step.strategy, "Unique Rectangle Type 6");
+    assert_eq!(step.units, [Unit::Row(0), Unit::Row(1)]);
+    assert_eq!(sorted(step.eliminations), [(0, 4, 1), (1, 0, 1)]);
+}
+
+/// A bivalue universal grave: two solutions, one the other with every digit
+/// shifted by one, put each digit twice in every unit. Each of `odd` also
+/// gets an extra 5.
+fn grave(odd: &[(usize, usize)]) -> Marks {
+    let mut marks = Marks::new();
+    for (r, c) in all_cells() {
+        let d = (r * 3 + r / 3 + c) % 9 + 1;
+        let mut digits = format!("{d}{}", d % 9 + 1);
+        if odd.contains(&(r, c)) {
+            digits.push('5');
+        }
+        marks = marks.cell((r, c), &digits);
+    }
+    marks
+}
+
+#[test]
+fn bug_plus_one() {
+    // Only 5 at (1,1) breaks the tie.
+    let marks = grave(&[(0, 0)]);
+    assert_eq!(marks.sudoku().candidates(0, 0).len(), 3);
+
+    let step = step(BugPlusOne, &marks);
+    assert_eq!(step.placements, [(0, 0, 5)]);
+    assert!(step.eliminations.is_empty());
+}
+
+#[test]
+fn no_bug_with_two_odd_cells() {
+    assert!(common::find(BugPlusOne, &grave(&[(0, 0), (8, 8)])).is_none());
+}
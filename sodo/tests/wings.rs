@@ -0,0 +1,77 @@
+mod common;
+
+use common::{Marks, except, row, sorted, step};
+use sodo::Detail;
+use sodo::strategy::{WWing, XYWing, XYZWing};
+
+#[test]
+fn xy_wing_with_a_pincer_in_the_pivot_box() {
+    let (pivot, box_pincer, row_pincer) = ((0, 0), (1, 2), (0, 6));
+    let marks = Marks::new()
+        .cell(pivot, "12")
+        .cell(box_pincer, "13")
+        .cell(row_pincer, "23");
+
+    let step = step(XYWing, &marks);
+    assert_eq!(
+        step.detail,
+        Detail::Wing {
+            pivot: vec![pivot],
+            pincers: vec![row_pincer, box_pincer],
+        }
+    );
+    // Cells seeing both pincers: row 1 of box 1, and row 2 of box 3.
+    assert_eq!(
+        sorted(step.eliminations),
+        [(0, 1, 3), (0, 2, 3), (1, 6, 3), (1, 7, 3), (1, 8, 3)]
+    );
+}
+
+#[test]
+fn xy_wing_needs_the_pincers_to_share_a_digit() {
+    let marks = Marks::new()
+        .cell((0, 0), "12")
+        .cell((1, 2), "13")
+        .cell((0, 6), "24");
+
+    assert!(common::find(XYWing, &marks).is_none());
+}
+
+#[test]
+fn xyz_wing_only_clears_cells_seeing_the_pivot_too() {
+    let (pivot, box_pincer, row_pincer) = ((0, 0), (1, 2), (0, 6));
+    let marks = Marks::new()
+        .cell(pivot, "123")
+        .cell(box_pincer, "13")
+        .cell(row_pincer, "23");
+
+    let step = step(XYZWing, &marks);
+    assert_eq!(
+        step.detail,
+        Detail::Wing {
+            pivot: vec![pivot],
+            pincers: vec![row_pincer, box_pincer],
+        }
+    );
+    assert_eq!(sorted(step.eliminations), [(0, 1, 3), (0, 2, 3)]);
+}
+
+#[test]
+fn w_wing_through_a_strong_link() {
+    // In row 5, 4 only fits in columns 1 and 9: one end sees each {4,7} cell,
+    // so one of them is 7.
+    let (a, b) = ((0, 0), (8, 8));
+    let marks = Marks::new()
+        .cells(&[a, b], "47")
+        .without(4, &except(row(4), &[(4, 0), (4, 8)]));
+
+    let step = step(WWing, &marks);
+    assert_eq!(
+        step.detail,
+        Detail::Wing {
+            pivot: vec![(4, 0), (4, 8)],
+            pincers: vec![a, b],
+        }
+    );
+    assert_eq!(sorted(step.eliminations), [(0, 8, 7), (8, 0, 7)]);
+}